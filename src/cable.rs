@@ -0,0 +1,177 @@
+//! Cable diagnostics through the cable tester and cable length registers of the PHYs.
+
+use core::time::Duration;
+
+use crate::hal::IgbHal;
+use crate::igb::Igb;
+use crate::{IgbError, IgbResult};
+
+/// Marvell page holding the advanced virtual cable tester registers.
+const VCT_PAGE: u16 = 7;
+/// Length to the end of the cable or to the fault of pair 0, the other pairs follow.
+const VCT_PAIR_LENGTH: u32 = 0x10;
+/// Status of the four pairs, one nibble each starting with pair 0.
+const VCT_STATUS: u32 = 0x14;
+/// Control of the test.
+const VCT_CONTROL: u32 = 0x15;
+/// Runs the test, self-clearing once it completes.
+const VCT_CONTROL_ENABLE: u16 = 1 << 15;
+/// Reports lengths in meters instead of centimeters.
+const VCT_CONTROL_UNIT_METERS: u16 = 1 << 10;
+/// Deadline for the cable tester to complete.
+const VCT_TIMEOUT: Duration = Duration::from_secs(2);
+/// Interval between two checks of the cable tester.
+const VCT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Marvell PHY Specific Status, holding a cable length estimate on the M88E1111.
+const M88_PHY_SPEC_STATUS: u32 = 0x11;
+/// Cable length estimate, three bits.
+const M88_PSSR_CABLE_LENGTH_SHIFT: u16 = 7;
+/// Bounds in meters of the ranges encoded in the cable length estimate.
+const M88_CABLE_LENGTH_TABLE: [u16; 6] = [0, 50, 80, 110, 140, 140];
+
+/// PHY Diagnostics Status of the 82580 and I350 internal PHY.
+const I82580_PHY_DIAG_STATUS: u32 = 0x1F;
+/// Cable length in meters, eight bits.
+const I82580_DSTATUS_CABLE_LENGTH_SHIFT: u16 = 2;
+/// Cable length reported while it is unknown.
+const I82580_CABLE_LENGTH_UNKNOWN: u16 = 0xFF;
+
+/// Condition of a twisted pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairStatus {
+    /// Properly terminated.
+    Ok,
+    /// Not connected at the far end, or cut.
+    Open,
+    /// Shorted within the pair or with another pair.
+    Short,
+    /// Not tested, or the test did not conclude.
+    Unknown,
+}
+
+/// Result of the cable test for a twisted pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CablePair {
+    /// Condition of the pair.
+    pub status: PairStatus,
+    /// Length of the pair in meters, or distance to the fault, when known.
+    pub length_m: Option<u16>,
+}
+
+impl PairStatus {
+    /// Decodes the four bit status of a pair reported by the advanced virtual cable tester.
+    pub fn from_vct(nibble: u16) -> Self {
+        match nibble & 0xF {
+            0b0001 => Self::Ok,
+            0b0010 => Self::Open,
+            // 0b0100 and above report a short with another pair
+            0b0011..=0b1000 => Self::Short,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl CablePair {
+    const UNKNOWN: Self = Self {
+        status: PairStatus::Unknown,
+        length_m: None,
+    };
+}
+
+/// Result of the cable test for the four pairs of the cable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CableDiagnostics {
+    /// Pairs A to D, in MDI order.
+    pub pairs: [CablePair; 4],
+}
+
+impl CableDiagnostics {
+    /// Decodes the cable length estimate in the PHY Specific Status `pssr` of the M88E1111, the
+    /// middle of the range it encodes.
+    pub fn from_m88_pssr(pssr: u16) -> Self {
+        let index = (pssr >> M88_PSSR_CABLE_LENGTH_SHIFT) & 0b111;
+        let length_m = M88_CABLE_LENGTH_TABLE
+            .get(index as usize..=index as usize + 1)
+            .map(|bounds| (bounds[0] + bounds[1]) / 2);
+        Self {
+            pairs: [CablePair {
+                status: PairStatus::Unknown,
+                length_m,
+            }; 4],
+        }
+    }
+
+    /// Returns the longest length measured on the pairs.
+    pub fn length_m(&self) -> Option<u16> {
+        self.pairs.iter().filter_map(|pair| pair.length_m).max()
+    }
+}
+
+/// Runs the advanced virtual cable tester of the Marvell Alaska PHYs and of the I210 internal
+/// PHY. The test drops the link, which comes back once autonegotiation completes again.
+pub(crate) fn vct_diagnostics<H: IgbHal>(igb: &Igb<H>) -> IgbResult<CableDiagnostics> {
+    let control = igb.read_mdi_paged(VCT_PAGE, VCT_CONTROL)?;
+    igb.write_mdi_paged(
+        VCT_PAGE,
+        VCT_CONTROL,
+        control | VCT_CONTROL_ENABLE | VCT_CONTROL_UNIT_METERS,
+    )?;
+    Igb::<H>::poll_every("cable test", VCT_POLL_INTERVAL, VCT_TIMEOUT, || {
+        match igb.read_mdi_paged(VCT_PAGE, VCT_CONTROL) {
+            Ok(control) if control & VCT_CONTROL_ENABLE != 0 => None,
+            result => Some(result),
+        }
+    })??;
+
+    let status = igb.read_mdi_paged(VCT_PAGE, VCT_STATUS)?;
+    let mut pairs = [CablePair::UNKNOWN; 4];
+    for (index, pair) in pairs.iter_mut().enumerate() {
+        pair.status = PairStatus::from_vct(status >> (4 * index));
+        if pair.status != PairStatus::Unknown {
+            pair.length_m = Some(igb.read_mdi_paged(VCT_PAGE, VCT_PAIR_LENGTH + index as u32)?);
+        }
+    }
+    debug!("cable test status {:#06x}", status);
+    Ok(CableDiagnostics { pairs })
+}
+
+/// Decodes the cable length estimate of the M88E1111, which has no cable tester reachable
+/// through the Alaska registers. The estimate only holds while the link is up at
+/// 1000 Mb/s.
+pub(crate) fn m88_cable_length<H: IgbHal>(igb: &Igb<H>) -> IgbResult<CableDiagnostics> {
+    Ok(CableDiagnostics::from_m88_pssr(
+        igb.read_mdi(M88_PHY_SPEC_STATUS)?,
+    ))
+}
+
+/// Reads the cable length measured by the 82580 and I350 internal PHY while the link is up at
+/// 1000 Mb/s. The PHY does not test the condition of the pairs.
+pub(crate) fn i82580_cable_length<H: IgbHal>(igb: &Igb<H>) -> IgbResult<CableDiagnostics> {
+    let length =
+        (igb.read_mdi(I82580_PHY_DIAG_STATUS)? >> I82580_DSTATUS_CABLE_LENGTH_SHIFT) & 0xFF;
+    if length == I82580_CABLE_LENGTH_UNKNOWN {
+        return Err(IgbError::HardwareState(
+            "cable length is only measured on 1000 Mb/s links",
+        ));
+    }
+    Ok(CableDiagnostics {
+        pairs: [CablePair {
+            status: PairStatus::Unknown,
+            length_m: Some(length),
+        }; 4],
+    })
+}
+
+impl<H: IgbHal> Igb<H> {
+    /// Tests the cable attached to the copper port, reporting the condition and length of each
+    /// pair as far as the PHY can measure them.
+    pub fn cable_diagnostics(&self) -> IgbResult<CableDiagnostics> {
+        if !self.has_copper_phy() {
+            return Err(IgbError::HardwareState("no copper phy to test the cable"));
+        }
+        let diagnostics = self.phy().cable_diagnostics(self)?;
+        info!("igb: cable diagnostics {:?}", diagnostics);
+        Ok(diagnostics)
+    }
+}
@@ -0,0 +1,156 @@
+//! Advanced receive and transmit descriptors, see sections 7.1.5 and 7.2.2 of the datasheet.
+
+use core::ptr;
+
+/// Descriptor Done.
+pub const RXD_STAT_DD: u32 = 1 << 0;
+/// End of Packet.
+pub const RXD_STAT_EOP: u32 = 1 << 1;
+
+/// Descriptor Done.
+pub const TXD_STAT_DD: u32 = 1 << 0;
+
+/// End of Packet.
+pub const TXD_CMD_EOP: u32 = 1 << 24;
+/// Insert FCS.
+pub const TXD_CMD_IFCS: u32 = 1 << 25;
+/// Report Status.
+pub const TXD_CMD_RS: u32 = 1 << 27;
+/// Descriptor Extension, set for advanced descriptors.
+pub const TXD_CMD_DEXT: u32 = 1 << 29;
+/// Advanced data descriptor type.
+pub const TXD_DTYP_DATA: u32 = 0b0011 << 20;
+/// Shift of the PAYLEN field in `olinfo_status`.
+pub const TXD_PAYLEN_SHIFT: u32 = 14;
+
+/// Advanced receive descriptor, read format.
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct AdvancedRxDescriptorRead {
+    /// Packet buffer address.
+    pub pkt_addr: u64,
+    /// Header buffer address.
+    pub hdr_addr: u64,
+}
+
+/// Advanced receive descriptor, write-back format.
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct AdvancedRxDescriptorWB {
+    /// RSS type, packet type and header length.
+    pub pkt_info: u32,
+    /// RSS hash or fragment checksum.
+    pub rss_hash: u32,
+    /// Extended status and errors.
+    pub status_error: u32,
+    /// Packet length.
+    pub length: u16,
+    /// VLAN tag.
+    pub vlan: u16,
+}
+
+/// Advanced receive descriptor.
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub union AdvancedRxDescriptor {
+    /// Format written by software.
+    pub read: AdvancedRxDescriptorRead,
+    /// Format written back by hardware.
+    pub wb: AdvancedRxDescriptorWB,
+}
+
+impl AdvancedRxDescriptor {
+    /// Points the descriptor at a packet buffer.
+    pub(crate) fn set_packet_address(&mut self, packet_buffer_address: u64) {
+        unsafe {
+            ptr::write_volatile(&mut self.read.pkt_addr as *mut u64, packet_buffer_address);
+        }
+    }
+
+    /// Clears the header address, which also clears the write-back status.
+    pub(crate) fn reset_status(&mut self) {
+        unsafe {
+            ptr::write_volatile(&mut self.read.hdr_addr as *mut u64, 0);
+        }
+    }
+
+    /// Returns the write-back status.
+    pub(crate) fn status(&self) -> u32 {
+        unsafe { ptr::read_volatile(&self.wb.status_error as *const u32) }
+    }
+
+    /// Whether hardware has written back this descriptor.
+    pub(crate) fn descriptor_done(&self) -> bool {
+        self.status() & RXD_STAT_DD != 0
+    }
+
+    /// Whether this descriptor holds the last buffer of a packet.
+    pub(crate) fn end_of_packet(&self) -> bool {
+        self.status() & RXD_STAT_EOP != 0
+    }
+
+    /// Returns the length of the received data.
+    pub(crate) fn length(&self) -> u16 {
+        unsafe { ptr::read_volatile(&self.wb.length as *const u16) }
+    }
+}
+
+/// Advanced transmit data descriptor, read format.
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct AdvancedTxDescriptorRead {
+    /// Data buffer address.
+    pub buffer_addr: u64,
+    /// Command, descriptor type and data length.
+    pub cmd_type_len: u32,
+    /// Payload length, offload options and status.
+    pub olinfo_status: u32,
+}
+
+/// Advanced transmit data descriptor, write-back format.
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct AdvancedTxDescriptorWB {
+    /// Reserved.
+    pub rsvd: u64,
+    /// Next sequence number seed, reserved for data descriptors.
+    pub nxtseq_seed: u32,
+    /// Status.
+    pub status: u32,
+}
+
+/// Advanced transmit data descriptor.
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub union AdvancedTxDescriptor {
+    /// Format written by software.
+    pub read: AdvancedTxDescriptorRead,
+    /// Format written back by hardware.
+    pub wb: AdvancedTxDescriptorWB,
+}
+
+impl AdvancedTxDescriptor {
+    /// Fills the descriptor for a single buffer packet of `len` bytes.
+    pub(crate) fn send(&mut self, packet_buffer_address: u64, len: u16) {
+        let len = len as u32;
+        unsafe {
+            ptr::write_volatile(
+                &mut self.read.buffer_addr as *mut u64,
+                packet_buffer_address,
+            );
+            ptr::write_volatile(
+                &mut self.read.cmd_type_len as *mut u32,
+                TXD_CMD_EOP | TXD_CMD_RS | TXD_CMD_IFCS | TXD_CMD_DEXT | TXD_DTYP_DATA | len,
+            );
+            ptr::write_volatile(
+                &mut self.read.olinfo_status as *mut u32,
+                len << TXD_PAYLEN_SHIFT,
+            );
+        }
+    }
+
+    /// Whether hardware has finished with this descriptor.
+    pub(crate) fn descriptor_done(&self) -> bool {
+        unsafe { ptr::read_volatile(&self.wb.status as *const u32) & TXD_STAT_DD != 0 }
+    }
+}
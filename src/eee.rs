@@ -0,0 +1,113 @@
+//! Energy Efficient Ethernet (IEEE 802.3az) of the I350 and I210 internal PHYs.
+
+use tock_registers::interfaces::{ReadWriteable, Readable};
+
+use crate::hal::IgbHal;
+use crate::igb::Igb;
+use crate::link::LinkModes;
+use crate::mac::IgbMacType;
+use crate::regs::{EEER, IPCNFG};
+use crate::{IgbError, IgbResult};
+
+/// EMI address of the EEE link partner ability of the I350 internal PHY.
+const I350_EEE_LP_ABILITY: u16 = 0x040F;
+/// MMD device holding the EEE link partner ability on the I210.
+const I210_EEE_LP_ABILITY_DEVICE: u8 = 7;
+/// EEE link partner ability register of the I210.
+const I210_EEE_LP_ABILITY: u16 = 0x3D;
+/// EEE ability of 100BASE-TX, in the EEE advertisement registers.
+const MMD_EEE_100TX: u16 = 1 << 1;
+/// EEE ability of 1000BASE-T.
+const MMD_EEE_1000T: u16 = 1 << 2;
+
+/// EEE state of the port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EeeStatus {
+    /// EEE is advertised and LPI enabled on both directions.
+    pub enabled: bool,
+    /// EEE was negotiated with the link partner.
+    pub negotiated: bool,
+    /// The receive path is in LPI.
+    pub rx_lpi: bool,
+    /// The transmit path is in LPI.
+    pub tx_lpi: bool,
+    /// Modes for which the link partner advertises EEE.
+    pub partner: LinkModes,
+}
+
+/// LPI transitions counted since the previous read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LpiCounters {
+    /// Number of times the receive path entered LPI.
+    pub rx: u32,
+    /// Number of times the transmit path entered LPI.
+    pub tx: u32,
+}
+
+impl<H: IgbHal> Igb<H> {
+    /// Fails unless the port runs EEE on a copper internal PHY.
+    fn check_eee(&self) -> IgbResult {
+        if !self.capabilities().eee {
+            return Err(IgbError::UnsupportedDevice(self.device_id()));
+        }
+        if !self.has_copper_phy() {
+            return Err(IgbError::HardwareState("eee needs a copper phy"));
+        }
+        Ok(())
+    }
+
+    /// Enables or disables the advertisement of EEE at 100 and 1000 Mb/s together with LPI, and
+    /// restarts autonegotiation for the advertisement to apply.
+    pub fn set_eee(&self, enable: bool) -> IgbResult {
+        self.check_eee()?;
+        let regs = self.regs();
+        regs.ipcnfg
+            .modify(IPCNFG::EEE_100M_AN.val(enable as u32) + IPCNFG::EEE_1G_AN.val(enable as u32));
+        regs.eeer.modify(
+            EEER::TX_LPI_EN.val(enable as u32)
+                + EEER::RX_LPI_EN.val(enable as u32)
+                + EEER::LPI_FC.val(enable as u32),
+        );
+        info!("igb: eee {}", if enable { "enabled" } else { "disabled" });
+        self.restart_phy_autoneg()
+    }
+
+    /// Returns the EEE state of the port and the EEE abilities of the link partner.
+    pub fn eee_status(&self) -> IgbResult<EeeStatus> {
+        self.check_eee()?;
+        let eeer = self.regs().eeer.extract();
+        let ipcnfg = self.regs().ipcnfg.extract();
+
+        let ability = match self.mac_type() {
+            IgbMacType::I350 => self.read_emi(I350_EEE_LP_ABILITY)?,
+            _ => self.read_mmd(I210_EEE_LP_ABILITY_DEVICE, I210_EEE_LP_ABILITY)?,
+        };
+        let mut partner = LinkModes::empty();
+        if ability & MMD_EEE_100TX != 0 {
+            partner = partner | LinkModes::SPEED_100_FULL;
+        }
+        if ability & MMD_EEE_1000T != 0 {
+            partner = partner | LinkModes::SPEED_1000_FULL;
+        }
+
+        Ok(EeeStatus {
+            enabled: (ipcnfg.is_set(IPCNFG::EEE_100M_AN) || ipcnfg.is_set(IPCNFG::EEE_1G_AN))
+                && eeer.is_set(EEER::TX_LPI_EN)
+                && eeer.is_set(EEER::RX_LPI_EN),
+            negotiated: eeer.is_set(EEER::EEE_NEG),
+            rx_lpi: eeer.is_set(EEER::RX_LPI_STATUS),
+            tx_lpi: eeer.is_set(EEER::TX_LPI_STATUS),
+            partner,
+        })
+    }
+
+    /// Returns the LPI entries counted since the previous call, the counters clear on read.
+    pub fn lpi_counters(&self) -> IgbResult<LpiCounters> {
+        self.check_eee()?;
+        let stats = &self.regs().stats;
+        Ok(LpiCounters {
+            rx: stats.rlpic.get(),
+            tx: stats.tlpic.get(),
+        })
+    }
+}
@@ -0,0 +1,158 @@
+//! IEEE 802.3x flow control, see section 3.7.5 of the datasheet.
+
+use tock_registers::interfaces::{ReadWriteable, Readable, Writeable};
+
+use crate::hal::IgbHal;
+use crate::igb::Igb;
+use crate::mdio::{PHY_AUTONEG_ADV, PHY_LP_ABILITY};
+use crate::phy::{Duplex, NWAY_AR_ASM_DIR, NWAY_AR_PAUSE};
+use crate::regs::{CTRL, FCRTH, FCRTL, FCTTV, RXPBS};
+use crate::IgbResult;
+
+/// Destination address of pause frames, low 32 bits.
+const FLOW_CONTROL_ADDRESS_LOW: u32 = 0x00C2_8001;
+/// Destination address of pause frames, high 16 bits.
+const FLOW_CONTROL_ADDRESS_HIGH: u32 = 0x0100;
+/// Ethertype of pause frames.
+const FLOW_CONTROL_TYPE: u32 = 0x8808;
+/// Pause time sent in XOFF frames, in 512 bit-time slots.
+const FC_PAUSE_TIME: u32 = 0xFFFF;
+/// Largest frame the receive buffer must still be able to hold once XOFF is sent.
+const MAX_FRAME_SIZE: u32 = 1522;
+/// RXPBS sizes in KB of the 82580 and I350, indexed by the encoded value.
+pub(crate) const RXPBS_82580_SIZES: [u32; 11] = [36, 72, 144, 1, 2, 4, 8, 16, 35, 70, 140];
+
+/// Flow control mode, requested or resolved by autonegotiation.
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NicResolution {
+    /// No pause frames are sent nor honoured.
+    IgbFcNone,
+    /// Received pause frames are honoured, none are sent.
+    IgbFcRxPause,
+    /// Pause frames are sent, received ones are ignored.
+    IgbFcTxPause,
+    /// Pause frames are sent and honoured.
+    IgbFcFull,
+}
+
+impl NicResolution {
+    /// Returns the PAUSE and ASM_DIR bits advertising this mode.
+    pub fn advertisement(self) -> (bool, bool) {
+        match self {
+            Self::IgbFcNone => (false, false),
+            // the symmetric pause bit cannot ask for reception only, resolution narrows it down
+            Self::IgbFcRxPause | Self::IgbFcFull => (true, true),
+            Self::IgbFcTxPause => (false, true),
+        }
+    }
+
+    /// Resolves the mode from the local and link partner PAUSE and ASM_DIR bits, following
+    /// table 28B-3 of IEEE 802.3, given the mode requested locally.
+    pub fn resolve(
+        self,
+        (local_pause, local_asm): (bool, bool),
+        (partner_pause, partner_asm): (bool, bool),
+    ) -> Self {
+        match (local_pause, local_asm, partner_pause, partner_asm) {
+            (true, _, true, _) if self == Self::IgbFcFull => Self::IgbFcFull,
+            (true, _, true, _) => Self::IgbFcRxPause,
+            (false, true, true, true) => Self::IgbFcTxPause,
+            (true, true, false, true) => Self::IgbFcRxPause,
+            _ => Self::IgbFcNone,
+        }
+    }
+
+    /// Whether received pause frames are honoured.
+    pub fn rx_pause(self) -> bool {
+        matches!(self, Self::IgbFcRxPause | Self::IgbFcFull)
+    }
+
+    /// Whether pause frames are sent.
+    pub fn tx_pause(self) -> bool {
+        matches!(self, Self::IgbFcTxPause | Self::IgbFcFull)
+    }
+}
+
+impl<H: IgbHal> Igb<H> {
+    /// Returns the receive packet buffer size in KB.
+    pub fn rx_packet_buffer_kb(&self) -> u32 {
+        let size = self.regs().rxpbs.read(RXPBS::SIZE);
+        if !self.capabilities().encoded_rxpbs {
+            return size;
+        }
+        RXPBS_82580_SIZES
+            .get((size & 0xF) as usize)
+            .copied()
+            .unwrap_or(self.capabilities().rx_packet_buffer_kb)
+    }
+
+    /// Programs the pause frame address, type and timer, and the receive thresholds derived from
+    /// the receive packet buffer size.
+    pub fn setup_flow_control(&self) {
+        let regs = self.regs();
+        regs.fcal.set(FLOW_CONTROL_ADDRESS_LOW);
+        regs.fcah.set(FLOW_CONTROL_ADDRESS_HIGH);
+        regs.fct.set(FLOW_CONTROL_TYPE);
+        regs.fcttv.write(FCTTV::TTV.val(FC_PAUSE_TIME));
+
+        // XOFF is sent once there is still room for a maximum size frame, and for 10% of the
+        // buffer in which the link partner reacts, XON 16 bytes below
+        let pba = self.rx_packet_buffer_kb() << 10;
+        let high_water = (pba * 9 / 10).min(pba.saturating_sub(2 * MAX_FRAME_SIZE)) & !0xF;
+        let low_water = high_water.saturating_sub(16);
+        regs.fcrtl
+            .write(FCRTL::RTL.val(low_water >> 4) + FCRTL::XONE::SET);
+        regs.fcrth.write(FCRTH::RTH.val(high_water >> 4));
+        debug!(
+            "flow control thresholds: low {:#x}, high {:#x}",
+            low_water, high_water
+        );
+    }
+
+    /// Returns the flow control mode advertised to the link partner.
+    pub fn requested_flow_control(&self) -> NicResolution {
+        self.fc_requested
+    }
+
+    /// Requests flow control `mode`, advertising it and restarting autonegotiation.
+    pub fn set_flow_control(&mut self, mode: NicResolution) -> IgbResult {
+        self.fc_requested = mode;
+        info!("igb: requesting flow control {:?}", mode);
+        self.renegotiate_link()
+    }
+
+    /// Returns the flow control mode the MAC currently applies.
+    pub fn flow_control(&self) -> NicResolution {
+        let ctrl = self.regs().ctrl.extract();
+        match (ctrl.is_set(CTRL::RFCE), ctrl.is_set(CTRL::TFCE)) {
+            (true, true) => NicResolution::IgbFcFull,
+            (true, false) => NicResolution::IgbFcRxPause,
+            (false, true) => NicResolution::IgbFcTxPause,
+            (false, false) => NicResolution::IgbFcNone,
+        }
+    }
+
+    /// Applies flow control `mode` to the MAC through CTRL.RFCE and CTRL.TFCE.
+    pub(crate) fn force_flow_control(&self, mode: NicResolution) {
+        self.regs().ctrl.modify(
+            CTRL::RFCE.val(mode.rx_pause() as u32) + CTRL::TFCE.val(mode.tx_pause() as u32),
+        );
+        debug!("flow control set to {:?}", mode);
+    }
+
+    /// Resolves flow control from the copper autonegotiation result and applies it to the MAC.
+    pub(crate) fn config_fc_after_link_up(&self, duplex: Duplex) -> IgbResult<NicResolution> {
+        // pause frames are only defined for full duplex links
+        let mode = if duplex == Duplex::Half {
+            NicResolution::IgbFcNone
+        } else {
+            let bits = |reg: u16| (reg & NWAY_AR_PAUSE != 0, reg & NWAY_AR_ASM_DIR != 0);
+            let local = bits(self.read_mdi(PHY_AUTONEG_ADV)?);
+            let partner = bits(self.read_mdi(PHY_LP_ABILITY)?);
+            self.fc_requested.resolve(local, partner)
+        };
+        self.force_flow_control(mode);
+        Ok(mode)
+    }
+}
@@ -61,6 +61,8 @@ where
     ///
     unsafe fn mmio_virt_to_phys(vaddr: NonNull<u8>, size: usize) -> PhysAddr;
 
-    /// Wait until reaching the given deadline.
+    /// Waits for at least `duration` from the call, a relative delay rather than a deadline. The
+    /// driver times its polling loops by adding up these delays, so implementations must not
+    /// return early unless they return an error.
     fn wait_until(duration: Duration) -> Result<(), &'static str>;
 }
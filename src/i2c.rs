@@ -0,0 +1,101 @@
+//! Access to the PHY of SGMII ports and to the SFP module EEPROM through the I2C interface of
+//! the MAC, see section 8.2.5 of the datasheet.
+
+use core::time::Duration;
+
+use tock_registers::interfaces::{ReadWriteable, Readable, Writeable};
+
+use crate::hal::IgbHal;
+use crate::igb::Igb;
+use crate::mac::IgbMacType;
+use crate::regs::{CTRL_EXT, I2CCMD, MDIC, MDICNFG};
+use crate::semaphore::SwFwResource;
+use crate::{IgbError, IgbResult};
+
+/// Deadline for an I2CCMD transaction.
+const I2CCMD_TIMEOUT: Duration = Duration::from_millis(10);
+/// Highest PHY address reachable through I2CCMD.
+pub(crate) const MAX_I2C_PHY_ADDR: u32 = 7;
+/// Offset of the SFP diagnostics page A2h, following page A0h.
+pub const SFP_DIAG_PAGE: u16 = 0x100;
+
+impl<H: IgbHal> Igb<H> {
+    /// Whether the port runs SGMII with its PHY reached through I2CCMD rather than MDIC.
+    pub(crate) fn sgmii_uses_i2c(&self) -> bool {
+        let regs = self.regs();
+        if regs.ctrl_ext.read_as_enum(CTRL_EXT::LINK_MODE)
+            != Some(CTRL_EXT::LINK_MODE::Value::Sgmii)
+        {
+            return false;
+        }
+        match self.mac_type() {
+            IgbMacType::Mac82575 | IgbMacType::Mac82576 => !regs.mdic.is_set(MDIC::DEST),
+            _ => !regs.mdicnfg.is_set(MDICNFG::DESTINATION),
+        }
+    }
+
+    /// Returns the address MDICNFG assigns to an external PHY on the 82580 and later.
+    pub(crate) fn mdicnfg_phy_addr(&self) -> Option<u32> {
+        if matches!(self.mac_type(), IgbMacType::Mac82575 | IgbMacType::Mac82576) {
+            return None;
+        }
+        let mdicnfg = self.regs().mdicnfg.extract();
+        mdicnfg
+            .is_set(MDICNFG::DESTINATION)
+            .then(|| mdicnfg.read(MDICNFG::PHY_ADDRESS))
+    }
+
+    /// Runs a single I2CCMD transaction on register `reg_addr` of I2C device `phy_addr`, with the
+    /// PHY semaphore already held, returning the DATA field.
+    fn i2ccmd(&self, phy_addr: u32, reg_addr: u32, write: Option<u16>) -> IgbResult<u16> {
+        let i2ccmd = &self.regs().i2ccmd;
+        let op = match write {
+            Some(value) => I2CCMD::DATA.val(value as u32) + I2CCMD::OP::Write,
+            None => I2CCMD::OP::Read,
+        };
+        i2ccmd.write(op + I2CCMD::REGADD.val(reg_addr) + I2CCMD::PHYADD.val(phy_addr));
+
+        let value = Self::poll("i2c transaction", I2CCMD_TIMEOUT, || {
+            let value = i2ccmd.extract();
+            value.is_set(I2CCMD::READY).then_some(value)
+        })?;
+        if value.is_set(I2CCMD::E) {
+            debug!("i2c error: device {}, reg {}", phy_addr, reg_addr);
+            return Err(IgbError::HardwareState(
+                "device did not answer the i2c transaction",
+            ));
+        }
+        Ok(value.read(I2CCMD::DATA) as u16)
+    }
+
+    /// Runs a single I2CCMD transaction on a PHY register, with the PHY semaphore already held.
+    pub(crate) fn i2ccmd_phy(
+        &self,
+        phy_addr: u32,
+        reg_addr: u32,
+        write: Option<u16>,
+    ) -> IgbResult<u16> {
+        if phy_addr > MAX_I2C_PHY_ADDR {
+            return Err(IgbError::InvalidArgument("i2c phy address above 7"));
+        }
+        // the PHY registers are big endian on the I2C bus
+        let value = self.i2ccmd(phy_addr, reg_addr, write.map(u16::swap_bytes))?;
+        Ok(value.swap_bytes())
+    }
+
+    /// Reads `buf.len()` bytes of the SFP module EEPROM from `offset`. Offsets below
+    /// [`SFP_DIAG_PAGE`] address page A0h, the following 256 bytes page A2h.
+    pub fn read_sfp(&self, offset: u16, buf: &mut [u8]) -> IgbResult {
+        if offset as usize + buf.len() > 2 * SFP_DIAG_PAGE as usize {
+            return Err(IgbError::InvalidArgument("sfp offset beyond page a2h"));
+        }
+        self.regs().ctrl_ext.modify(CTRL_EXT::I2C_ENA::SET);
+        let _phy = self.acquire_swfw(SwFwResource::Phy)?;
+        for (offset, byte) in (offset..).zip(buf.iter_mut()) {
+            // the page is selected by the lowest bit of the device address field
+            let value = self.i2ccmd((offset >> 8) as u32, (offset & 0xFF) as u32, None)?;
+            *byte = value as u8;
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,33 @@
-#![allow()]
-use core::{default, ptr::NonNull};
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::mem;
+use core::ptr::NonNull;
+use core::time::Duration;
 
-use log::{debug, info};
+use log::{debug, error, info, warn};
+use tock_registers::interfaces::{ReadWriteable, Readable, Writeable};
+
+use crate::descriptor::{AdvancedRxDescriptor, AdvancedTxDescriptor};
+use crate::flow_control::{NicResolution, RXPBS_82580_SIZES};
+use crate::hal::IgbHal;
+use crate::link::{LinkModes, LinkState};
+use crate::mac::{IgbMacType, IgbNvmType, IgbPhyType, MacCapabilities};
+use crate::mdio::{DEFAULT_PHY_ADDR, PHY_1000T_CTRL, PHY_AUTONEG_ADV};
+use crate::memory::{alloc_pkt, Dma, MemPool, Packet};
+use crate::phy::{
+    Duplex, I210Phy, I82580Phy, IgpPhy, LinkSpeed, M88Phy, Phy, CR_1000T_FD_CAPS, CR_1000T_HD_CAPS,
+    NWAY_AR_ASM_DIR, NWAY_AR_PAUSE,
+};
+use crate::regs::{
+    IgbRegisters, RxQueueRegs, TxQueueRegs, CTRL, CTRL_EXT, LEGACY_QUEUE_STRIDE,
+    LEGACY_RX_QUEUE_BASE, LEGACY_TX_QUEUE_BASE, NUM_RAR, RAH, RCTL, RXDCTL, RXPBS, SRRCTL, STATUS,
+    TCTL, TIPG, TXDCTL, TXPBS,
+};
+use crate::semaphore::SwFwResource;
+use crate::{IgbError, IgbResult, NicDevice};
 
 const DRIVER_NAME: &str = "igb";
 
@@ -11,227 +37,1217 @@ const MAX_QUEUES: u16 = 64;
 const PKT_BUF_ENTRY_SIZE: usize = 2048;
 const MIN_MEMPOOL_SIZE: usize = 4096;
 //* 意义不明的三个常量 */
+/// Interval between two reads of a register being polled.
+const POLL_INTERVAL: Duration = Duration::from_micros(10);
+/// Deadline for CTRL.RST to self-clear.
+const RESET_TIMEOUT: Duration = Duration::from_millis(100);
+/// Deadline for RXDCTL.ENABLE or TXDCTL.ENABLE to read back as set.
+const QUEUE_ENABLE_TIMEOUT: Duration = Duration::from_millis(10);
+/// Deadline for copper autonegotiation to resolve a speed and duplex.
+const AUTONEG_TIMEOUT: Duration = Duration::from_secs(5);
+/// Interval between two checks of the autonegotiation state.
+const AUTONEG_POLL_INTERVAL: Duration = Duration::from_millis(100);
 
-
-enum NicResolution{
-    IgbFcNone,
-    IgbFcRxPause,
-    IgbFcTxPause,
-    IgbFcFull,
+/// Low level handle of an igb controller, owning the mapped BAR0 register space.
+pub struct Igb<H: IgbHal> {
+    bar0: NonNull<u8>,
+    device_id: u16,
+    mac_type: IgbMacType,
+    perm_addr: [u8; 6],
+    phy_addr: u32,
+    phy_i2c: bool,
+    phy_id: u32,
+    phy_type: IgbPhyType,
+    sfp_cage: bool,
+    pub(crate) fc_requested: NicResolution,
+    pub(crate) advertised: LinkModes,
+    _marker: PhantomData<H>,
 }
 
-pub struct Igb {bar0: NonNull<u8>}
+impl<H: IgbHal> Igb<H> {
+    /// Takes over the controller with PCI `device_id` mapped at `bar0`, disabling interrupts and
+    /// issuing a global reset.
+    pub fn new(bar0: NonNull<u8>, device_id: u16) -> IgbResult<Self> {
+        let mac_type =
+            IgbMacType::from_device_id(device_id).ok_or(IgbError::UnsupportedDevice(device_id))?;
+        info!("igb: device {:#06x}, mac type {:?}", device_id, mac_type);
 
-impl Igb {
+        let mut igb = Igb {
+            bar0,
+            device_id,
+            mac_type,
+            perm_addr: [0; 6],
+            phy_addr: DEFAULT_PHY_ADDR,
+            phy_i2c: false,
+            phy_id: 0,
+            phy_type: mac_type.capabilities().phy,
+            sfp_cage: false,
+            fc_requested: NicResolution::IgbFcFull,
+            advertised: LinkModes::COPPER,
+            _marker: PhantomData,
+        };
 
-    const MDIO_ADDRESS: u32 = 0b00001;
+        igb.disable_interrupts()?;
+        igb.global_reset()?;
+        igb.read_status();
+        if igb.nvm_type() != IgbNvmType::Invm {
+            igb.validate_nvm_checksum()?;
+        }
+        igb.perm_addr = igb.read_perm_addr()?;
+        info!("igb: permanent address {:02x?}", igb.perm_addr);
 
-    pub fn new(bar0: NonNull<u8>) -> Self {
-        let igb = Igb {bar0};
+        // SerDes ports take an SFP module, whose media decides the link mode
+        igb.sfp_cage = igb.regs().ctrl_ext.read_as_enum(CTRL_EXT::LINK_MODE)
+            == Some(CTRL_EXT::LINK_MODE::Value::SerDes);
+        igb.detect_phy();
 
-        igb.disable_interrupts();
-        igb.global_reset();
-        igb.read_status();
+        Ok(igb)
+    }
 
-        return igb;
+    /// Looks for the PHY on the MDIO or I2C bus of the current link mode, and records its
+    /// address, identifier and type.
+    pub(crate) fn detect_phy(&mut self) {
+        self.phy_i2c = self.sgmii_uses_i2c();
+        self.regs()
+            .ctrl_ext
+            .modify(CTRL_EXT::I2C_ENA.val(self.phy_i2c as u32));
+        match self.scan_phy() {
+            Ok((addr, id)) => {
+                self.phy_addr = addr;
+                self.phy_id = id;
+                match IgbPhyType::from_phy_id(id) {
+                    Some(phy_type) => self.phy_type = phy_type,
+                    None => warn!(
+                        "igb: unknown phy {:#010x}, assuming {:?}",
+                        id, self.phy_type
+                    ),
+                }
+            }
+            // serdes ports have no PHY on the mdio bus
+            Err(e) => {
+                self.phy_id = 0;
+                warn!("igb: no phy found: {:?}", e);
+            }
+        }
+    }
+
+    /// Returns the typed register block mapped by BAR0.
+    pub(crate) fn regs(&self) -> &IgbRegisters {
+        unsafe { self.bar0.cast::<IgbRegisters>().as_ref() }
+    }
+
+    /// Returns the registers of receive queue `n`.
+    pub(crate) fn rx_queue_regs(&self, n: usize) -> &RxQueueRegs {
+        if self.capabilities().legacy_queue_regs {
+            let offset = LEGACY_RX_QUEUE_BASE + n * LEGACY_QUEUE_STRIDE;
+            unsafe { self.bar0.add(offset).cast::<RxQueueRegs>().as_ref() }
+        } else {
+            &self.regs().rx_queues[n]
+        }
+    }
+
+    /// Returns the registers of transmit queue `n`.
+    pub(crate) fn tx_queue_regs(&self, n: usize) -> &TxQueueRegs {
+        if self.capabilities().legacy_queue_regs {
+            let offset = LEGACY_TX_QUEUE_BASE + n * LEGACY_QUEUE_STRIDE;
+            unsafe { self.bar0.add(offset).cast::<TxQueueRegs>().as_ref() }
+        } else {
+            &self.regs().tx_queues[n]
+        }
     }
 
-    pub fn disable_interrupts(&self) {
-        unsafe { self.bar0.add(0x0150C).cast::<u32>().write_volatile(0x0000_0000) };
+    /// Returns the PCI device ID.
+    pub fn device_id(&self) -> u16 {
+        self.device_id
+    }
+
+    /// Returns the MAC family.
+    pub fn mac_type(&self) -> IgbMacType {
+        self.mac_type
+    }
+
+    /// Returns the MDIO or I2C address of the PHY.
+    pub fn phy_addr(&self) -> u32 {
+        self.phy_addr
+    }
+
+    /// Whether the PHY is reached through I2CCMD rather than MDIC.
+    pub fn phy_over_i2c(&self) -> bool {
+        self.phy_i2c
+    }
+
+    /// Returns the identifier read from the PHY at initialization, or once an SFP module needs
+    /// one, or 0 if none was found.
+    pub fn phy_id(&self) -> u32 {
+        self.phy_id
+    }
+
+    /// Whether the port is a SerDes port taking an SFP module.
+    pub fn has_sfp_cage(&self) -> bool {
+        self.sfp_cage
+    }
+
+    /// Returns the type of the PHY.
+    pub fn phy_type(&self) -> IgbPhyType {
+        self.phy_type
+    }
+
+    /// Returns the driver of the PHY.
+    pub fn phy(&self) -> &dyn Phy<H> {
+        match self.phy_type {
+            IgbPhyType::M88 => &M88Phy,
+            IgbPhyType::Igp => &IgpPhy,
+            IgbPhyType::Internal82580 => &I82580Phy,
+            IgbPhyType::InternalI210 => &I210Phy,
+        }
+    }
+
+    /// Returns the capabilities and quirks of the MAC family.
+    pub fn capabilities(&self) -> &'static MacCapabilities {
+        self.mac_type.capabilities()
+    }
+
+    /// Calls `f` every [`POLL_INTERVAL`] until it returns a value, giving up with
+    /// [`IgbError::Timeout`] naming `op` once `timeout` has elapsed.
+    pub(crate) fn poll<T>(
+        op: &'static str,
+        timeout: Duration,
+        f: impl FnMut() -> Option<T>,
+    ) -> IgbResult<T> {
+        Self::poll_every(op, POLL_INTERVAL, timeout, f)
+    }
+
+    /// Like [`Self::poll`], waiting `interval` between two calls to `f`.
+    pub(crate) fn poll_every<T>(
+        op: &'static str,
+        interval: Duration,
+        timeout: Duration,
+        mut f: impl FnMut() -> Option<T>,
+    ) -> IgbResult<T> {
+        let mut elapsed = Duration::ZERO;
+        loop {
+            if let Some(value) = f() {
+                return Ok(value);
+            }
+            if elapsed >= timeout {
+                error!("{} timed out after {:?}", op, timeout);
+                return Err(IgbError::Timeout(op));
+            }
+            H::wait_until(interval).map_err(|_| IgbError::Timeout(op))?;
+            elapsed += interval;
+        }
+    }
+
+    /// Masks all interrupt causes through IMC.
+    pub fn disable_interrupts(&self) -> IgbResult {
+        self.regs().imc.set(u32::MAX);
         info!("start disable interrupts");
 
-        let ims = unsafe { self.bar0.add(0x01508).cast::<u32>().read_volatile() };
-        
+        let ims = self.regs().ims.get();
+
         if ims != 0 {
-            panic!("interrupts not disabled");
+            error!("interrupts not disabled, ims: {:#x}", ims);
+            return Err(IgbError::HardwareState(
+                "interrupts still enabled after IMC write",
+            ));
         }
 
         debug!("interrupts disabled");
+        Ok(())
     }
 
-    pub fn global_reset(&self) {
+    /// Issues a port software reset through CTRL.RST and waits for it to self-clear.
+    pub fn global_reset(&self) -> IgbResult {
         // global reset , ILOS is initally set to 0
-        let ctrl = unsafe { self.bar0.cast::<u32>().read_volatile() };
-        unsafe { self.bar0.cast::<u32>().write_volatile(ctrl | 1<<26) };
+        self.regs().ctrl.modify(CTRL::RST::SET);
 
         info!("start reset");
 
-        loop {
-            let ctr = unsafe { self.bar0.cast::<u32>().read_volatile() };
-            if ctr & (1<<26) == 0 {
-                break;
-            }
-        }
+        Self::poll("global reset", RESET_TIMEOUT, || {
+            (!self.regs().ctrl.is_set(CTRL::RST)).then_some(())
+        })?;
 
         debug!("reset");
 
+        // the device needs about 1 ms before its registers can be accessed again
+        let _ = H::wait_until(Duration::from_millis(1));
+
         // the interrupts need to be disabled also after issuing a global reset
-        self.disable_interrupts();
+        self.disable_interrupts()
     }
 
+    /// Logs the duplex, link and speed bits of STATUS.
     pub fn read_status(&self) {
-        let status = unsafe { self.bar0.add(0x00008).cast::<u32>().read_volatile() };
-        let fd = status & 1<<0;
-        let lu = status & 1<<1;
-        let speed = (status & 0b11 <<6)>>6;
+        let status = self.regs().status.extract();
+        let fd = status.read(STATUS::FD);
+        let lu = status.read(STATUS::LU);
+        let speed = status.read(STATUS::SPEED);
 
         debug!("igb status : fd: {}, lu: {}, speed: {:#b}", fd, lu, speed);
     }
 
-    pub fn disable_rx_tx_flow(&self){
+    /// Returns the link speed in Mb/s, or 0 if the link is down.
+    pub fn link_speed(&self) -> u16 {
+        let status = self.regs().status.extract();
+        if !status.is_set(STATUS::LU) {
+            return 0;
+        }
+        match status.read_as_enum(STATUS::SPEED) {
+            Some(STATUS::SPEED::Value::Speed10) => 10,
+            Some(STATUS::SPEED::Value::Speed100) => 100,
+            _ => 1000,
+        }
+    }
+
+    /// Returns the address held in receive address register 0.
+    pub fn mac_addr(&self) -> [u8; 6] {
+        let rar = &self.regs().rar[0];
+        let low = rar.ral.get();
+        let high = rar.rah.read(RAH::RAH);
+        [
+            low as u8,
+            (low >> 8) as u8,
+            (low >> 16) as u8,
+            (low >> 24) as u8,
+            high as u8,
+            (high >> 8) as u8,
+        ]
+    }
+
+    /// Returns the permanent address read from the NVM at initialization.
+    pub fn perm_mac_addr(&self) -> [u8; 6] {
+        self.perm_addr
+    }
+
+    /// Reads the permanent address from the NVM, falling back to the one left in RAR[0] by the
+    /// firmware.
+    fn read_perm_addr(&self) -> IgbResult<[u8; 6]> {
+        match self.read_nvm_mac_addr() {
+            Ok(addr) if is_valid_mac_addr(&addr) => return Ok(addr),
+            Ok(addr) => warn!("invalid address in nvm: {:02x?}", addr),
+            Err(e) => warn!("failed to read address from nvm: {:?}", e),
+        }
+
+        let addr = self.mac_addr();
+        if is_valid_mac_addr(&addr) {
+            Ok(addr)
+        } else {
+            error!("no valid address in nvm nor in RAR[0]: {:02x?}", addr);
+            Err(IgbError::HardwareState("no valid station address"))
+        }
+    }
+
+    /// Programs `addr` in receive address register 0 and marks it valid.
+    pub fn set_mac_addr(&self, addr: [u8; 6]) -> IgbResult {
+        if !is_valid_mac_addr(&addr) {
+            return Err(IgbError::InvalidArgument(
+                "station address must be a non-zero unicast address",
+            ));
+        }
+
+        let rar = &self.regs().rar[0];
+        // invalidate the entry while both halves are being updated
+        rar.rah.modify(RAH::AV::CLEAR);
+        rar.ral
+            .set(u32::from_le_bytes([addr[0], addr[1], addr[2], addr[3]]));
+        rar.rah
+            .write(RAH::RAH.val(u16::from_le_bytes([addr[4], addr[5]]) as u32) + RAH::AV::SET);
+        debug!("station address set to {:02x?}", addr);
+        Ok(())
+    }
+
+    /// Programs the permanent address in RAR[0] and clears the other receive address registers.
+    pub fn init_rx_addrs(&self) -> IgbResult {
+        self.set_mac_addr(self.perm_addr)?;
+        let entries = (self.capabilities().rar_entries as usize).min(NUM_RAR);
+        for rar in &self.regs().rar[1..entries] {
+            rar.rah.set(0);
+            rar.ral.set(0);
+        }
+        Ok(())
+    }
+
+    /// Tells the firmware that a driver has taken over the device.
+    pub fn set_driver_loaded(&self) {
+        self.regs().ctrl_ext.modify(CTRL_EXT::DRV_LOAD::SET);
+        debug!("driver loaded");
+    }
+
+    /// Resets the PHY through CTRL.PHY_RST, unless the management controller blocks it.
+    pub fn phy_reset(&self) -> IgbResult {
+        if self.phy_reset_blocked() {
+            info!("igb: phy reset blocked by the management controller");
+            return Ok(());
+        }
+        let _phy = self.acquire_swfw(SwFwResource::Phy)?;
+        info!("start phy reset");
+        self.regs().ctrl.modify(CTRL::PHY_RST::SET);
+        let pulse = H::wait_until(Duration::from_micros(100));
+        // release the PHY from reset even if the pulse could not be timed
+        self.regs().ctrl.modify(CTRL::PHY_RST::CLEAR);
+        pulse.map_err(|_| IgbError::Timeout("phy reset"))?;
+        // wait for the PHY to load its configuration
+        H::wait_until(Duration::from_millis(10)).map_err(|_| IgbError::Timeout("phy reset"))?;
+        debug!("phy reset");
+        Ok(())
+    }
+
+    /// Sets the link up, letting the MAC follow the speed and duplex resolved by the PHY, and
+    /// restarts autonegotiation.
+    pub fn setup_link(&self) -> IgbResult {
+        self.regs().ctrl.modify(
+            CTRL::SLU::SET
+                + CTRL::LRST::CLEAR
+                + CTRL::ILOS::CLEAR
+                + CTRL::FRCSPD::CLEAR
+                + CTRL::FRCDPLX::CLEAR,
+        );
+        debug!("set link up");
+
+        self.setup_flow_control();
+
+        self.phy_setup_autoneg()
+    }
+
+    /// Forces the speed and duplex of a copper link on both the MAC and the PHY, disabling
+    /// autonegotiation. 1000BASE-T cannot run without autonegotiation, so only 10 and 100 Mb/s
+    /// can be forced.
+    pub fn force_link(&self, speed: LinkSpeed, duplex: Duplex) -> IgbResult {
+        if speed == LinkSpeed::Speed1000 {
+            return Err(IgbError::InvalidArgument(
+                "1000 Mb/s copper links need autonegotiation",
+            ));
+        }
+        if self.phy_id() == 0 {
+            return Err(IgbError::HardwareState("no phy to force the link on"));
+        }
+
+        let mac_speed = match speed {
+            LinkSpeed::Speed10 => CTRL::SPEED::Speed10,
+            _ => CTRL::SPEED::Speed100,
+        };
+        self.regs().ctrl.modify(
+            CTRL::SLU::SET
+                + CTRL::LRST::CLEAR
+                + CTRL::ILOS::CLEAR
+                + CTRL::FRCSPD::SET
+                + CTRL::FRCDPLX::SET
+                + mac_speed
+                + CTRL::FD.val((duplex == Duplex::Full) as u32),
+        );
+        self.phy().force_speed_duplex(self, speed, duplex)?;
+
+        // nothing is negotiated, pause frames follow the request on full duplex links
+        self.force_flow_control(if duplex == Duplex::Full {
+            self.fc_requested
+        } else {
+            NicResolution::IgbFcNone
+        });
+        info!(
+            "igb: link forced to {} Mb/s {:?} duplex",
+            speed.mbps(),
+            duplex
+        );
+        Ok(())
+    }
+
+    /// Returns a link forced by [`Self::force_link`] to autonegotiation.
+    pub fn restore_autoneg(&self) -> IgbResult {
+        info!("igb: link back to autonegotiation");
+        self.setup_link()
+    }
+
+    /// Advertises the requested copper speeds and duplex modes with the pause bits of the
+    /// requested flow control mode, then restarts autonegotiation.
+    pub(crate) fn phy_setup_autoneg(&self) -> IgbResult {
+        let modes = self.advertised_modes();
+        let (pause, asm_dir) = self.fc_requested.advertisement();
+        let pause_bits = NWAY_AR_PAUSE | NWAY_AR_ASM_DIR;
+        let mut adv =
+            self.read_mdi(PHY_AUTONEG_ADV)? & !(LinkModes::COPPER.nway_bits() | pause_bits);
+        adv |= modes.nway_bits();
+        if pause {
+            adv |= NWAY_AR_PAUSE;
+        }
+        if asm_dir {
+            adv |= NWAY_AR_ASM_DIR;
+        }
+        self.write_mdi(PHY_AUTONEG_ADV, adv)?;
+
+        let ctrl_1000 = self.read_mdi(PHY_1000T_CTRL)? & !(CR_1000T_HD_CAPS | CR_1000T_FD_CAPS);
+        self.write_mdi(PHY_1000T_CTRL, ctrl_1000 | modes.ctrl_1000t_bits())?;
+
+        self.phy().restart_autoneg(self)?;
+        debug!("autonegotiation restarted");
+        Ok(())
+    }
+
+    /// Clears the statistics registers, which are cleared on read.
+    pub fn clear_statistics(&self) {
+        let stats = &self.regs().stats;
+        for reg in [
+            &stats.crcerrs,
+            &stats.algnerrc,
+            &stats.symerrs,
+            &stats.rxerrc,
+            &stats.mpc,
+            &stats.scc,
+            &stats.ecol,
+            &stats.mcc,
+            &stats.latecol,
+            &stats.colc,
+            &stats.dc,
+            &stats.tncrs,
+            &stats.sec,
+            &stats.cexterr,
+            &stats.rlec,
+            &stats.xonrxc,
+            &stats.xontxc,
+            &stats.xoffrxc,
+            &stats.xofftxc,
+            &stats.fcruc,
+            &stats.prc64,
+            &stats.prc127,
+            &stats.prc255,
+            &stats.prc511,
+            &stats.prc1023,
+            &stats.prc1522,
+            &stats.gprc,
+            &stats.bprc,
+            &stats.mprc,
+            &stats.gptc,
+            &stats.gorcl,
+            &stats.gorch,
+            &stats.gotcl,
+            &stats.gotch,
+            &stats.rnbc,
+            &stats.ruc,
+            &stats.rfc,
+            &stats.roc,
+            &stats.rjc,
+            &stats.mgtprc,
+            &stats.mgtpdc,
+            &stats.mgtptc,
+            &stats.torl,
+            &stats.torh,
+            &stats.totl,
+            &stats.toth,
+            &stats.tpr,
+            &stats.tpt,
+            &stats.ptc64,
+            &stats.ptc127,
+            &stats.ptc255,
+            &stats.ptc511,
+            &stats.ptc1023,
+            &stats.ptc1522,
+            &stats.mptc,
+            &stats.bptc,
+            &stats.tsctc,
+            &stats.tsctfc,
+            &stats.iac,
+        ] {
+            reg.get();
+        }
+        debug!("statistics cleared");
+    }
+
+    /// Clears RCTL.EN and TCTL.EN.
+    pub fn disable_rx_tx_flow(&self) {
         info!("disable RX and TX flow");
-        let rctl = unsafe { self.bar0.add(0x00100).cast::<u32>().read_volatile() };
-        unsafe { self.bar0.add(0x00100).cast::<u32>().write_volatile(rctl&(!(1<<1))) };
+        self.regs().rctl.modify(RCTL::EN::CLEAR);
         debug!("Rx flow disabled");
-        let tctl = unsafe { self.bar0.add(0x00400).cast::<u32>().read_volatile() };
-        unsafe { self.bar0.add(0x00400).cast::<u32>().write_volatile(tctl&(!(1<<1))) };
+        self.regs().tctl.modify(TCTL::EN::CLEAR);
         debug!("Tx flow disabled");
     }
 
-    pub fn enable_rx_tx_flow(&self){
+    /// Sets RCTL.EN and TCTL.EN.
+    pub fn enable_rx_tx_flow(&self) {
         info!("enable RX and TX flow");
-        let rctl = unsafe { self.bar0.add(0x00100).cast::<u32>().read_volatile() };
-        unsafe { self.bar0.add(0x00100).cast::<u32>().write_volatile(rctl|(1<<1)) };
+        self.regs().rctl.modify(RCTL::EN::SET);
         debug!("Rx flow enabled");
-        let tctl = unsafe { self.bar0.add(0x00400).cast::<u32>().read_volatile() };
-        unsafe { self.bar0.add(0x00400).cast::<u32>().write_volatile(tctl|(1<<1)) };
+        self.regs().tctl.modify(TCTL::EN::SET);
         debug!("Tx flow enabled");
     }
 
-    pub fn set_rx_packet_buffer_size(&self, size: u32) {
-        if size > 0b0111_1111{
-            panic!("size too big");
-        }
+    /// Sets the receive packet buffer size in KB. The 82580 and I350 only take the sizes of
+    /// their RXPBS encoding.
+    pub fn set_rx_packet_buffer_size(&self, size: u32) -> IgbResult {
+        let value =
+            if self.capabilities().encoded_rxpbs {
+                RXPBS_82580_SIZES.iter().position(|&kb| kb == size).ok_or(
+                    IgbError::InvalidArgument("rx packet buffer size not encodable in rxpbs"),
+                )? as u32
+            } else if size > 0b0111_1111 {
+                return Err(IgbError::InvalidArgument(
+                    "rx packet buffer size above 127 KB",
+                ));
+            } else {
+                size
+            };
         self.disable_rx_tx_flow();
-        unsafe { self.bar0.add(0x2404).cast::<u32>().write_volatile(size) };
+        self.regs().rxpbs.write(RXPBS::SIZE.val(value));
         self.enable_rx_tx_flow();
+        Ok(())
     }
 
-    pub fn set_tx_packet_buffer_size(&self, size: u32) {
-        if size > 0b0011_1111{
-            panic!("size too big");
+    /// Sets the transmit packet buffer size in KB.
+    pub fn set_tx_packet_buffer_size(&self, size: u32) -> IgbResult {
+        if size > 0b0011_1111 {
+            return Err(IgbError::InvalidArgument(
+                "tx packet buffer size above 63 KB",
+            ));
         }
         self.disable_rx_tx_flow();
-        unsafe { self.bar0.add(0x3404).cast::<u32>().write_volatile(size) };
+        self.regs().txpbs.write(TXPBS::SIZE.val(size));
         self.enable_rx_tx_flow();
+        Ok(())
     }
 
-    pub fn forcing_mac_speed(&self, speed: u32) {
+    /// Forces the MAC speed using the raw CTRL.SPEED encoding.
+    pub fn forcing_mac_speed(&self, speed: u32) -> IgbResult {
         if speed > 0b11 {
-            panic!("speed too big");
+            return Err(IgbError::InvalidArgument(
+                "speed encoding wider than 2 bits",
+            ));
         }
         if speed == 0b11 {
-            panic!("speed 0b11 not used");
+            return Err(IgbError::InvalidArgument("speed encoding 0b11 is not used"));
         }
-        let ctrl = unsafe { self.bar0.cast::<u32>().read_volatile() };
-        unsafe { self.bar0.cast::<u32>().write_volatile(ctrl | (1<<11) ) };
-        let ctrl = unsafe { self.bar0.cast::<u32>().read_volatile() };
-        let frcspd = (ctrl & (1<<11))>>11;
+        self.regs().ctrl.modify(CTRL::FRCSPD::SET);
+        let frcspd = self.regs().ctrl.read(CTRL::FRCSPD);
         debug!("frcspd set to {:#b}", frcspd);
         //? 这里有一点非常重要,就是在设置一个位的时候,假如我们不知道其原始状态,我们应该先将其清零,然后再设置
         //? 一句话,就是要考虑原状态
-        unsafe { self.bar0.cast::<u32>().write_volatile(ctrl & (!(0b11<<8)) | (speed<<8) ) };
+        self.regs().ctrl.modify(CTRL::SPEED.val(speed));
         debug!("speed set to {:#b}", speed);
+        Ok(())
     }
 
+    /// Lets the MAC follow the speed indicated by the internal PHY.
     pub fn using_internal_phy_direct_linkspeed_indication(&self) {
-        let ctrl = unsafe { self.bar0.cast::<u32>().read_volatile() };
-        unsafe { self.bar0.cast::<u32>().write_volatile(ctrl & (!(1<<11)) ) };
+        self.regs().ctrl.modify(CTRL::FRCSPD::CLEAR);
         // CTRL-Bit5 Reserved. Must be set to 0b.- Was ASDE
         debug!("using internal phy direct link speed indication");
-        let status = unsafe { self.bar0.add(0x00008).cast::<u32>().read_volatile() };
-        let speed = status & 0b11 <<6;
+        let speed = self.regs().status.read(STATUS::SPEED);
         debug!("igb status : speed: {:#b}", speed);
     }
 
-    pub fn forcing_duplex_mode(&self, duplex: u32) {
+    /// Forces the MAC duplex, `1` for full duplex and `0` for half duplex.
+    pub fn forcing_duplex_mode(&self, duplex: u32) -> IgbResult {
         if duplex > 0b1 {
-            panic!("no such duplex mode");
+            return Err(IgbError::InvalidArgument("no such duplex mode"));
         }
-        let ctrl = unsafe { self.bar0.cast::<u32>().read_volatile() };
-        unsafe { self.bar0.cast::<u32>().write_volatile(ctrl | (1<<12) ) };
-        let ctrl = unsafe { self.bar0.cast::<u32>().read_volatile() };
-        let frcdplx = (ctrl & (1<<12))>>12;
+        self.regs().ctrl.modify(CTRL::FRCDPLX::SET);
+        let frcdplx = self.regs().ctrl.read(CTRL::FRCDPLX);
         debug!("frcdplx set to {:#b}", frcdplx);
-        unsafe { self.bar0.cast::<u32>().write_volatile(ctrl & !(1) | duplex) };
+        self.regs().ctrl.modify(CTRL::FD.val(duplex));
         debug!("duplex set to {:#b}", duplex);
+        Ok(())
     }
 
-    pub fn read_mdi(&self,reg_addr: u32) -> u32 {
-        //* R: 1<<28 0b  */
-        let mdic = 0x0000_0000 | 
-                        reg_addr<<16 | 
-                        Self::MDIO_ADDRESS<<21 | 
-                        0b10<<26 | 
-                        1<<28 ;
-        unsafe { self.bar0.add(0x00020).cast::<u32>().write_volatile(mdic) };
-        loop {
-            let mdic = unsafe { self.bar0.add(0x00020).cast::<u32>().read_volatile() };
-            if mdic & 1<<30 == 1 {
-                debug!("mdi read e");
-                return 0;
-            }
-            if mdic & 1<<28 == 0 {
-                debug!("read mdi done");
-                return mdic&0xFFFF;
-            }
+    /// Sets up the link of a copper port through its PHY, waits for autonegotiation to complete
+    /// and returns the negotiated speed and duplex.
+    pub fn phy_link_setup(&self) -> IgbResult<(LinkSpeed, Duplex)> {
+        self.regs()
+            .ctrl_ext
+            .modify(CTRL_EXT::LINK_MODE::DirectCopper);
+        debug!("phy link mode direct copper");
+
+        self.setup_link()?;
+
+        let phy = self.phy();
+        let (speed, duplex) = Self::poll_every(
+            "autonegotiation",
+            AUTONEG_POLL_INTERVAL,
+            AUTONEG_TIMEOUT,
+            || phy.speed_duplex(self).transpose(),
+        )??;
+        let fc = self.config_fc_after_link_up(duplex)?;
+        info!(
+            "igb: link up, {} Mb/s {:?} duplex, flow control {:?}",
+            speed.mbps(),
+            duplex,
+            fc
+        );
+        Ok((speed, duplex))
+    }
+}
+
+/// A network buffer used by the igb driver.
+pub struct IgbNetBuf {
+    inner: Packet,
+}
+
+impl IgbNetBuf {
+    /// Allocates a buffer of `size` bytes from `pool`.
+    pub fn alloc(pool: &Arc<MemPool>, size: usize) -> IgbResult<Self> {
+        if let Some(pkt) = alloc_pkt(pool, size) {
+            Ok(Self { inner: pkt })
+        } else {
+            Err(IgbError::NoMemory)
         }
     }
 
-    pub fn write_mdi(&self,reg_addr: u32, value: u32) {
-        if value > 0xFFFF {
-            panic!("value too big");
+    /// Returns an unmutuable packet buffer.
+    pub fn packet(&self) -> &[u8] {
+        self.inner.as_bytes()
+    }
+
+    /// Returns a mutuable packet buffer.
+    pub fn packet_mut(&mut self) -> &mut [u8] {
+        self.inner.as_mut_bytes()
+    }
+
+    /// Returns the length of the packet.
+    pub fn packet_len(&self) -> usize {
+        self.inner.len
+    }
+
+    /// Returns the entry of the packet in its memory pool.
+    pub fn pool_entry(&self) -> usize {
+        self.inner.pool_entry
+    }
+
+    /// Constructs a buffer from the free `pool_entry` of `pool` holding `len` bytes, taking the
+    /// entry out of the pool.
+    pub fn construct(pool_entry: usize, pool: &Arc<MemPool>, len: usize) -> IgbResult<Self> {
+        if pool_entry >= pool.num_entries() {
+            return Err(IgbError::InvalidArgument(
+                "pool entry outside of the memory pool",
+            ));
         }
-        //* W: 1<<29 0b  */
-        let mdic = 0x0000_0000 | 
-                        reg_addr<<16 | 
-                        Self::MDIO_ADDRESS<<21 | 
-                        0b01<<26 | 
-                        1<<29 | 
-                        value;
-        unsafe { self.bar0.add(0x00020).cast::<u32>().write_volatile(mdic) };
-        loop {
-            let mdic = unsafe { self.bar0.add(0x00020).cast::<u32>().read_volatile() };
-            if mdic & 1<<30 == 1 {
-                debug!("mdi write e");
-                return;
+        if len > pool.entry_size() {
+            return Err(IgbError::NoMemory);
+        }
+        if !pool.claim_buf(pool_entry) {
+            return Err(IgbError::InvalidArgument("pool entry already in use"));
+        }
+        Ok(Self::from_entry(pool_entry, pool, len))
+    }
+
+    /// Wraps the `pool_entry` of `pool`, already taken out of the pool, holding `len` bytes.
+    fn from_entry(pool_entry: usize, pool: &Arc<MemPool>, len: usize) -> Self {
+        let pkt = unsafe {
+            Packet::new(
+                pool.get_virt_addr(pool_entry),
+                pool.get_phys_addr(pool_entry),
+                len,
+                Arc::clone(pool),
+                pool_entry,
+            )
+        };
+        Self { inner: pkt }
+    }
+}
+
+/// An initialized igb network device with `QS` descriptors per queue.
+pub struct IgbDevice<H: IgbHal, const QS: usize> {
+    igb: Igb<H>,
+    num_rx_queues: u16,
+    num_tx_queues: u16,
+    rx_queues: Vec<IgbRxQueue>,
+    tx_queues: Vec<IgbTxQueue>,
+    link_state: LinkState,
+    link_callback: Option<Box<dyn FnMut(LinkState) + Send>>,
+}
+
+struct IgbRxQueue {
+    descriptors: Box<[NonNull<AdvancedRxDescriptor>]>,
+    num_descriptors: usize,
+    pool: Arc<MemPool>,
+    bufs_in_use: Vec<usize>,
+    rx_index: usize,
+}
+
+impl IgbRxQueue {
+    fn can_recv(&self) -> bool {
+        unsafe { self.descriptors[self.rx_index].as_ref() }.descriptor_done()
+    }
+}
+
+struct IgbTxQueue {
+    descriptors: Box<[NonNull<AdvancedTxDescriptor>]>,
+    num_descriptors: usize,
+    bufs_in_use: VecDeque<IgbNetBuf>,
+    clean_index: usize,
+    tx_index: usize,
+}
+
+impl IgbTxQueue {
+    fn can_send(&self) -> bool {
+        wrap_ring(self.tx_index, self.num_descriptors) != self.clean_index
+    }
+}
+
+fn wrap_ring(index: usize, ring_size: usize) -> usize {
+    (index + 1) % ring_size
+}
+
+/// Whether `addr` can be used as a station address: neither zero nor multicast.
+fn is_valid_mac_addr(addr: &[u8; 6]) -> bool {
+    addr[0] & 1 == 0 && addr.iter().any(|&b| b != 0)
+}
+
+impl<H: IgbHal, const QS: usize> IgbDevice<H, QS> {
+    /// Brings up the controller with PCI `device_id` mapped at `bar0` following the initialization
+    /// sequence of datasheet section 4.5, and returns it with all queues enabled.
+    ///
+    /// Receive buffers are taken from `pool`, whose entry size must be a multiple of 1 KB.
+    pub fn new(
+        bar0: NonNull<u8>,
+        device_id: u16,
+        num_rx_queues: u16,
+        num_tx_queues: u16,
+        pool: &Arc<MemPool>,
+    ) -> IgbResult<Self> {
+        let caps = IgbMacType::from_device_id(device_id)
+            .ok_or(IgbError::UnsupportedDevice(device_id))?
+            .capabilities();
+        if num_rx_queues == 0
+            || num_rx_queues > caps.rx_queues
+            || num_tx_queues == 0
+            || num_tx_queues > caps.tx_queues
+        {
+            return Err(IgbError::InvalidQueue);
+        }
+        // RDLEN and TDLEN must be multiples of 128 bytes
+        if QS == 0 || QS % 8 != 0 {
+            return Err(IgbError::QueueNotAligned);
+        }
+        if pool.entry_size() % 1024 != 0 {
+            return Err(IgbError::PageNotAligned);
+        }
+
+        info!("igb: initializing device");
+
+        // interrupts disabled, global reset
+        let igb = Igb::<H>::new(bar0, device_id)?;
+        let mut dev = IgbDevice {
+            igb,
+            num_rx_queues,
+            num_tx_queues,
+            rx_queues: Vec::with_capacity(num_rx_queues as usize),
+            tx_queues: Vec::with_capacity(num_tx_queues as usize),
+            link_state: LinkState::DOWN,
+            link_callback: None,
+        };
+
+        // general configuration
+        dev.igb.set_driver_loaded();
+        dev.igb.init_rx_addrs()?;
+
+        // PHY and link setup
+        if dev.igb.has_copper_phy() {
+            dev.igb.phy().reset(&dev.igb)?;
+        }
+        dev.igb.power_up_phy()?;
+        dev.igb.bring_up_link()?;
+
+        // statistics
+        dev.igb.clear_statistics();
+
+        // receive and transmit initialization
+        dev.init_rx(pool)?;
+        dev.init_tx()?;
+
+        for i in 0..num_rx_queues {
+            dev.start_rx_queue(i)?;
+        }
+        for i in 0..num_tx_queues {
+            dev.start_tx_queue(i)?;
+        }
+
+        dev.igb.enable_rx_tx_flow();
+        dev.igb.read_status();
+
+        info!("igb: device initialized");
+
+        Ok(dev)
+    }
+
+    /// Returns the low level controller handle.
+    pub fn igb(&self) -> &Igb<H> {
+        &self.igb
+    }
+
+    /// Returns the number of receive queues.
+    pub fn num_rx_queues(&self) -> u16 {
+        self.num_rx_queues
+    }
+
+    /// Returns the number of transmit queues.
+    pub fn num_tx_queues(&self) -> u16 {
+        self.num_tx_queues
+    }
+
+    /// Registers `callback`, called by [`Self::check_link`] whenever the link state changes.
+    pub fn set_link_callback(&mut self, callback: impl FnMut(LinkState) + Send + 'static) {
+        self.link_callback = Some(Box::new(callback));
+    }
+
+    /// Returns the link state seen by the last call to [`Self::check_link`].
+    pub fn last_link_state(&self) -> LinkState {
+        self.link_state
+    }
+
+    /// Brings the interface down: stops reception and transmission and powers the PHY down, so
+    /// the link partner sees the link go down.
+    pub fn close(&mut self) -> IgbResult {
+        self.igb.disable_rx_tx_flow();
+        self.igb.power_down_phy()
+    }
+
+    /// Brings the interface back up after [`Self::close`]: powers the PHY up, restarts
+    /// autonegotiation and resumes reception and transmission.
+    pub fn open(&mut self) -> IgbResult {
+        self.igb.power_up_phy()?;
+        self.igb.bring_up_link()?;
+        self.igb.enable_rx_tx_flow();
+        Ok(())
+    }
+
+    /// Polls the link state, resolving flow control when the link comes up and calling the link
+    /// callback if the state changed since the previous call.
+    pub fn check_link(&mut self) -> IgbResult<LinkState> {
+        let mut state = self.igb.link_state();
+        if state.up && !self.link_state.up {
+            self.igb.resolve_flow_control(state.duplex)?;
+            state.flow_control = self.igb.flow_control();
+        }
+
+        if state != self.link_state {
+            if state.up {
+                info!(
+                    "igb: link up, {} Mb/s {:?} duplex, flow control {:?}",
+                    state.speed.mbps(),
+                    state.duplex,
+                    state.flow_control
+                );
+            } else {
+                info!("igb: link down");
             }
-            if mdic & 1<<28 == 1 {
-                debug!("write mdi done");
-                return;
+            self.link_state = state;
+            if let Some(callback) = self.link_callback.as_mut() {
+                callback(state);
             }
         }
+
+        Ok(state)
     }
 
-    pub fn phy_link_setup(&self) {
-        let mut ctrl_ext = unsafe { self.bar0.add(0x00018).cast::<u32>().read_volatile() };
-        ctrl_ext = ctrl_ext & !(0b11<<22) | 0b00<<22;
-        unsafe { self.bar0.add(0x00018).cast::<u32>().write_volatile(ctrl_ext) };
-        debug!("phy link mode direct copper");
-        let ana = self.read_mdi(4);
-        let anbpa = self.read_mdi(5);
-        let local = ana & 0b11<<10;
-        let partner = anbpa & 0b11<<10;
-        match local {
-            0 => todo!(),
-            default => todo!()
+    /// Requests flow control `mode` and restarts autonegotiation.
+    pub fn set_flow_control(&mut self, mode: NicResolution) -> IgbResult {
+        self.igb.set_flow_control(mode)
+    }
+
+    /// Restricts the advertised link modes to `modes` and restarts autonegotiation.
+    pub fn set_advertised_modes(&mut self, modes: LinkModes) -> IgbResult {
+        self.igb.set_advertised_modes(modes)
+    }
+
+    /// Overrides the station address programmed in RAR[0].
+    pub fn set_mac_addr(&mut self, addr: [u8; 6]) -> IgbResult {
+        self.igb.set_mac_addr(addr)
+    }
+
+    fn init_rx(&mut self, pool: &Arc<MemPool>) -> IgbResult {
+        let regs = self.igb.regs();
+
+        // the receiver stays disabled while the queues are set up
+        regs.rctl.modify(RCTL::EN::CLEAR);
+
+        // no multicast addresses
+        for mta in regs.mta.iter() {
+            mta.set(0);
+        }
+
+        for i in 0..self.num_rx_queues as usize {
+            debug!("initializing rx queue {}", i);
+
+            let ring_size_bytes = QS * mem::size_of::<AdvancedRxDescriptor>();
+            let dma: Dma<AdvancedRxDescriptor, H> = Dma::allocate(ring_size_bytes, true)?;
+            let descriptors = (0..QS)
+                .map(|id| {
+                    let mut desc = NonNull::new(unsafe { dma.virt.add(id) }).unwrap();
+                    unsafe { desc.as_mut().reset_status() };
+                    desc
+                })
+                .collect::<Vec<_>>()
+                .into_boxed_slice();
+
+            let queue = self.igb.rx_queue_regs(i);
+            queue.rdbal.set((dma.phys & 0xffff_ffff) as u32);
+            queue.rdbah.set((dma.phys >> 32) as u32);
+            queue.rdlen.set(ring_size_bytes as u32);
+            queue.srrctl.write(
+                SRRCTL::BSIZEPACKET.val((pool.entry_size() / 1024) as u32)
+                    + SRRCTL::DESCTYPE::AdvancedOneBuffer
+                    + SRRCTL::DROP_EN::SET,
+            );
+            queue.rdh.set(0);
+            queue.rdt.set(0);
+
+            self.rx_queues.push(IgbRxQueue {
+                descriptors,
+                num_descriptors: QS,
+                pool: Arc::clone(pool),
+                bufs_in_use: Vec::with_capacity(QS),
+                rx_index: 0,
+            });
         }
+
+        // accept broadcast, strip CRC
+        regs.rctl
+            .write(RCTL::BAM::SET + RCTL::SECRC::SET + RCTL::RDMTS::Half + RCTL::LBM::Normal);
+
+        Ok(())
     }
 
-    pub fn serdes_link_setup(&self) {
-        todo!()
+    fn start_rx_queue(&mut self, queue_id: u16) -> IgbResult {
+        debug!("starting rx queue {}", queue_id);
+
+        let queue = &mut self.rx_queues[queue_id as usize];
+
+        for i in 0..queue.num_descriptors {
+            let id = queue.pool.alloc_buf().ok_or(IgbError::NoMemory)?;
+            unsafe {
+                let desc = queue.descriptors[i].as_mut();
+                desc.set_packet_address(queue.pool.get_phys_addr(id) as u64);
+                desc.reset_status();
+            }
+            queue.bufs_in_use.push(id);
+        }
+
+        let regs = self.igb.rx_queue_regs(queue_id as usize);
+        regs.rxdctl.write(
+            RXDCTL::PTHRESH.val(8)
+                + RXDCTL::HTHRESH.val(8)
+                + RXDCTL::WTHRESH.val(1)
+                + RXDCTL::ENABLE::SET,
+        );
+        Igb::<H>::poll("rx queue enable", QUEUE_ENABLE_TIMEOUT, || {
+            regs.rxdctl.is_set(RXDCTL::ENABLE).then_some(())
+        })?;
+
+        // hand all descriptors to the hardware
+        regs.rdh.set(0);
+        regs.rdt.set((queue.num_descriptors - 1) as u32);
+
+        Ok(())
     }
 
-    pub fn sgmii_link_setup(&self) {
-        todo!()
+    fn init_tx(&mut self) -> IgbResult {
+        let regs = self.igb.regs();
+
+        regs.tctl.modify(TCTL::EN::CLEAR);
+
+        for i in 0..self.num_tx_queues as usize {
+            debug!("initializing tx queue {}", i);
+
+            let ring_size_bytes = QS * mem::size_of::<AdvancedTxDescriptor>();
+            let dma: Dma<AdvancedTxDescriptor, H> = Dma::allocate(ring_size_bytes, true)?;
+            let descriptors = (0..QS)
+                .map(|id| NonNull::new(unsafe { dma.virt.add(id) }).unwrap())
+                .collect::<Vec<_>>()
+                .into_boxed_slice();
+
+            let queue = self.igb.tx_queue_regs(i);
+            queue.tdbal.set((dma.phys & 0xffff_ffff) as u32);
+            queue.tdbah.set((dma.phys >> 32) as u32);
+            queue.tdlen.set(ring_size_bytes as u32);
+            queue.tdh.set(0);
+            queue.tdt.set(0);
+            queue
+                .txdctl
+                .write(TXDCTL::PTHRESH.val(8) + TXDCTL::HTHRESH.val(1) + TXDCTL::WTHRESH.val(1));
+
+            self.tx_queues.push(IgbTxQueue {
+                descriptors,
+                num_descriptors: QS,
+                bufs_in_use: VecDeque::with_capacity(QS),
+                clean_index: 0,
+                tx_index: 0,
+            });
+        }
+
+        regs.tipg
+            .write(TIPG::IPGT.val(8) + TIPG::IPGR1.val(8) + TIPG::IPGR2.val(6));
+        regs.tctl
+            .write(TCTL::PSP::SET + TCTL::CT.val(0x0F) + TCTL::COLD.val(0x3F) + TCTL::RTLC::SET);
+
+        Ok(())
     }
 
-}
+    fn start_tx_queue(&mut self, queue_id: u16) -> IgbResult {
+        debug!("starting tx queue {}", queue_id);
 
-pub struct IgbNetBuf {
+        let regs = self.igb.tx_queue_regs(queue_id as usize);
+        regs.txdctl.modify(TXDCTL::ENABLE::SET);
+        Igb::<H>::poll("tx queue enable", QUEUE_ENABLE_TIMEOUT, || {
+            regs.txdctl.is_set(TXDCTL::ENABLE).then_some(())
+        })?;
 
+        Ok(())
+    }
 }
 
-pub struct IgbDevice {
+impl<H: IgbHal, const QS: usize> NicDevice<H> for IgbDevice<H, QS> {
+    fn get_driver_name(&self) -> &str {
+        DRIVER_NAME
+    }
+
+    fn get_mac_addr(&self) -> [u8; 6] {
+        self.igb.mac_addr()
+    }
+
+    fn reset_stats(&mut self) {
+        self.igb.clear_statistics();
+    }
+
+    fn get_link_speed(&self) -> u16 {
+        self.igb.link_speed()
+    }
+
+    fn recycle_tx_buffers(&mut self, queue_id: u16) -> IgbResult {
+        let queue = self
+            .tx_queues
+            .get_mut(queue_id as usize)
+            .ok_or(IgbError::InvalidQueue)?;
+
+        let mut clean_index = queue.clean_index;
+        let tx_index = queue.tx_index;
+
+        while clean_index != tx_index {
+            let desc = unsafe { queue.descriptors[clean_index].as_ref() };
+            if !desc.descriptor_done() {
+                break;
+            }
+
+            // dropping the buffer returns it to its pool
+            queue.bufs_in_use.pop_front();
+
+            clean_index = wrap_ring(clean_index, queue.num_descriptors);
+        }
+
+        queue.clean_index = clean_index;
+
+        Ok(())
+    }
+
+    fn receive_packets<F>(
+        &mut self,
+        queue_id: u16,
+        packet_nums: usize,
+        mut f: F,
+    ) -> IgbResult<usize>
+    where
+        F: FnMut(IgbNetBuf),
+    {
+        let queue = self
+            .rx_queues
+            .get_mut(queue_id as usize)
+            .ok_or(IgbError::InvalidQueue)?;
+
+        if !queue.can_recv() {
+            return Err(IgbError::NotReady);
+        }
+
+        let mut recv_nums = 0;
+        let mut rx_index = queue.rx_index;
+
+        for _ in 0..packet_nums {
+            let desc = unsafe { queue.descriptors[rx_index].as_mut() };
+            if !desc.descriptor_done() {
+                break;
+            }
+
+            let len = desc.length() as usize;
+            if desc.end_of_packet() && len <= queue.pool.entry_size() {
+                // replace the filled buffer with a fresh one from the pool
+                let Some(buf) = queue.pool.alloc_buf() else {
+                    error!("no free buffer in the memory pool");
+                    break;
+                };
+                let filled = mem::replace(&mut queue.bufs_in_use[rx_index], buf);
+                let rx_buf = IgbNetBuf::from_entry(filled, &queue.pool, len);
+                desc.set_packet_address(queue.pool.get_phys_addr(buf) as u64);
+                desc.reset_status();
+
+                f(rx_buf);
+                recv_nums += 1;
+            } else {
+                // packets never span buffers with long packet reception disabled
+                warn!("dropping packet spanning multiple descriptors or longer than a buffer");
+                desc.set_packet_address(
+                    queue.pool.get_phys_addr(queue.bufs_in_use[rx_index]) as u64
+                );
+                desc.reset_status();
+            }
+
+            rx_index = wrap_ring(rx_index, queue.num_descriptors);
+        }
+
+        if rx_index != queue.rx_index {
+            // hand the processed descriptors back to the hardware
+            let tail = (rx_index + queue.num_descriptors - 1) % queue.num_descriptors;
+            self.igb
+                .rx_queue_regs(queue_id as usize)
+                .rdt
+                .set(tail as u32);
+            queue.rx_index = rx_index;
+        }
+
+        Ok(recv_nums)
+    }
+
+    fn send(&mut self, queue_id: u16, tx_buf: IgbNetBuf) -> IgbResult {
+        let queue = self
+            .tx_queues
+            .get_mut(queue_id as usize)
+            .ok_or(IgbError::InvalidQueue)?;
+
+        if !queue.can_send() {
+            return Err(IgbError::QueueFull);
+        }
+
+        let Ok(len) = u16::try_from(tx_buf.packet_len()) else {
+            return Err(IgbError::InvalidArgument(
+                "packet longer than a transmit descriptor",
+            ));
+        };
+
+        let tx_index = queue.tx_index;
+        let desc = unsafe { queue.descriptors[tx_index].as_mut() };
+        desc.send(tx_buf.inner.get_phys_addr() as u64, len);
+
+        // the buffer is returned to its pool by `recycle_tx_buffers`
+        queue.bufs_in_use.push_back(tx_buf);
+
+        queue.tx_index = wrap_ring(tx_index, queue.num_descriptors);
+        self.igb
+            .tx_queue_regs(queue_id as usize)
+            .tdt
+            .set(queue.tx_index as u32);
+
+        Ok(())
+    }
+
+    fn can_receive(&self, queue_id: u16) -> IgbResult<bool> {
+        let queue = self
+            .rx_queues
+            .get(queue_id as usize)
+            .ok_or(IgbError::InvalidQueue)?;
+        Ok(queue.can_recv())
+    }
+
+    fn can_send(&self, queue_id: u16) -> IgbResult<bool> {
+        let queue = self
+            .tx_queues
+            .get(queue_id as usize)
+            .ok_or(IgbError::InvalidQueue)?;
+        Ok(queue.can_send())
+    }
+}
 
-}
\ No newline at end of file
+unsafe impl<H: IgbHal, const QS: usize> Sync for IgbDevice<H, QS> {}
+unsafe impl<H: IgbHal, const QS: usize> Send for IgbDevice<H, QS> {}
@@ -1,23 +1,48 @@
 //! Intel 82599+ 10Gb NIC Driver Implementation.
 
 #![no_std]
+#![recursion_limit = "256"]
 #![deny(warnings)]
 #![deny(missing_docs)]
 #![allow(dead_code)]
 
+mod cable;
 mod constants;
 mod descriptor;
+mod eee;
+mod flow_control;
 mod hal;
+mod i2c;
 mod interrupts;
 mod igb;
+mod link;
+mod loopback;
+mod mac;
+mod mdio;
 mod memory;
+mod nvm;
+mod pcs;
+mod phy;
+mod power;
+mod regs;
+mod semaphore;
+mod sfp;
 
 extern crate alloc;
 #[macro_use]
 extern crate log;
 
+pub use cable::{CableDiagnostics, CablePair, PairStatus};
+pub use eee::{EeeStatus, LpiCounters};
+pub use flow_control::NicResolution;
 pub use hal::IgbHal;
-pub use igb::{IgbDevice, IgbNetBuf};
+pub use i2c::SFP_DIAG_PAGE;
+pub use igb::{Igb, IgbDevice, IgbNetBuf};
+pub use link::{LinkModes, LinkState};
+pub use loopback::Loopback;
+pub use mac::{IgbMacType, IgbNvmType, IgbPhyType, MacCapabilities};
+pub use phy::{Duplex, I210Phy, I82580Phy, IgpPhy, LinkSpeed, M88Phy, MdiMode, Phy};
+pub use sfp::{SfpDiagnostics, SfpMedia, SfpModule, SFP_DDM_LEN, SFP_ID_LEN};
 
 pub use memory::{alloc_pkt, MemPool, PhysAddr};
 
@@ -42,6 +67,18 @@ pub enum IgbError {
     NotReady,
     /// Invalid `queue_id`.
     InvalidQueue,
+    /// A hardware operation did not complete before its deadline.
+    Timeout(&'static str),
+    /// An argument is outside of the range accepted by the hardware.
+    InvalidArgument(&'static str),
+    /// The hardware is not in the state the driver expects.
+    HardwareState(&'static str),
+    /// The PCI device ID does not belong to the igb family.
+    UnsupportedDevice(u16),
+    /// The NVM words sum up to the contained value instead of 0xBABA.
+    NvmChecksum(u16),
+    /// A resource shared with the firmware could not be taken before its deadline.
+    SemaphoreTimeout(&'static str),
 }
 
 /// Result type for Ixgbe functions.
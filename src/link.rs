@@ -0,0 +1,339 @@
+//! Link state reporting.
+
+use core::ops::{BitAnd, BitOr};
+
+use tock_registers::interfaces::Readable;
+
+use crate::flow_control::NicResolution;
+use crate::hal::IgbHal;
+use crate::igb::Igb;
+use crate::mac::IgbMacType;
+use crate::mdio::{PHY_1000T_STATUS, PHY_CONTROL, PHY_LP_ABILITY};
+use crate::phy::{
+    Duplex, LinkSpeed, MdiMode, CR_1000T_FD_CAPS, CR_1000T_HD_CAPS, MAX_DOWNSHIFT_RETRIES,
+    MII_CR_AUTO_NEG_EN, NWAY_AR_100TX_FD, NWAY_AR_100TX_HD, NWAY_AR_10T_FD, NWAY_AR_10T_HD,
+    SR_1000T_LP_FD_CAPS, SR_1000T_LP_HD_CAPS,
+};
+use crate::regs::{CTRL, CTRL_EXT, PCS_AN, PCS_LCTL, STATUS};
+use crate::{IgbError, IgbResult};
+
+/// State of the link as seen by the MAC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinkState {
+    /// Whether the link is up.
+    pub up: bool,
+    /// Speed of the link, meaningless while it is down.
+    pub speed: LinkSpeed,
+    /// Duplex mode of the link, meaningless while it is down.
+    pub duplex: Duplex,
+    /// Flow control mode applied by the MAC.
+    pub flow_control: NicResolution,
+    /// Crossover resolved by the copper PHY, `None` while the link is down or without a PHY
+    /// reporting it.
+    pub mdi: Option<MdiMode>,
+}
+
+impl LinkState {
+    /// State reported while the link is down.
+    pub const DOWN: Self = Self {
+        up: false,
+        speed: LinkSpeed::Speed10,
+        duplex: Duplex::Half,
+        flow_control: NicResolution::IgbFcNone,
+        mdi: None,
+    };
+}
+
+/// Set of speed and duplex combinations, supported, advertised or announced by the link partner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LinkModes(u8);
+
+impl LinkModes {
+    /// 10 Mb/s half duplex.
+    pub const SPEED_10_HALF: Self = Self(1 << 0);
+    /// 10 Mb/s full duplex.
+    pub const SPEED_10_FULL: Self = Self(1 << 1);
+    /// 100 Mb/s half duplex.
+    pub const SPEED_100_HALF: Self = Self(1 << 2);
+    /// 100 Mb/s full duplex.
+    pub const SPEED_100_FULL: Self = Self(1 << 3);
+    /// 1000 Mb/s half duplex.
+    pub const SPEED_1000_HALF: Self = Self(1 << 4);
+    /// 1000 Mb/s full duplex.
+    pub const SPEED_1000_FULL: Self = Self(1 << 5);
+    /// Modes of the copper ports, the MAC does not support 1000 Mb/s half duplex.
+    pub const COPPER: Self = Self(
+        Self::SPEED_10_HALF.0
+            | Self::SPEED_10_FULL.0
+            | Self::SPEED_100_HALF.0
+            | Self::SPEED_100_FULL.0
+            | Self::SPEED_1000_FULL.0,
+    );
+
+    /// Returns the empty set.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns the mode running at `speed` and `duplex`.
+    pub const fn from_speed_duplex(speed: LinkSpeed, duplex: Duplex) -> Self {
+        let mode = match speed {
+            LinkSpeed::Speed10 => Self::SPEED_10_HALF,
+            LinkSpeed::Speed100 => Self::SPEED_100_HALF,
+            LinkSpeed::Speed1000 => Self::SPEED_1000_HALF,
+        };
+        match duplex {
+            // each full duplex mode is the bit above its half duplex counterpart
+            Duplex::Full => Self(mode.0 << 1),
+            Duplex::Half => mode,
+        }
+    }
+
+    /// Whether every mode of `other` is in this set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether the set is empty.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the advertisement register bits of the 10 and 100 Mb/s modes.
+    pub(crate) fn nway_bits(self) -> u16 {
+        [
+            (Self::SPEED_10_HALF, NWAY_AR_10T_HD),
+            (Self::SPEED_10_FULL, NWAY_AR_10T_FD),
+            (Self::SPEED_100_HALF, NWAY_AR_100TX_HD),
+            (Self::SPEED_100_FULL, NWAY_AR_100TX_FD),
+        ]
+        .into_iter()
+        .filter(|&(mode, _)| self.contains(mode))
+        .fold(0, |bits, (_, bit)| bits | bit)
+    }
+
+    /// Returns the 1000BASE-T control register bits of the 1000 Mb/s modes.
+    pub(crate) fn ctrl_1000t_bits(self) -> u16 {
+        let mut bits = 0;
+        if self.contains(Self::SPEED_1000_HALF) {
+            bits |= CR_1000T_HD_CAPS;
+        }
+        if self.contains(Self::SPEED_1000_FULL) {
+            bits |= CR_1000T_FD_CAPS;
+        }
+        bits
+    }
+}
+
+impl BitOr for LinkModes {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitAnd for LinkModes {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl<H: IgbHal> Igb<H> {
+    /// Returns the link state from STATUS and the flow control bits of CTRL.
+    pub fn link_state(&self) -> LinkState {
+        let status = self.regs().status.extract();
+        if !status.is_set(STATUS::LU) {
+            return LinkState::DOWN;
+        }
+        let speed = match status.read_as_enum(STATUS::SPEED) {
+            Some(STATUS::SPEED::Value::Speed10) => LinkSpeed::Speed10,
+            Some(STATUS::SPEED::Value::Speed100) => LinkSpeed::Speed100,
+            _ => LinkSpeed::Speed1000,
+        };
+        let duplex = if status.is_set(STATUS::FD) {
+            Duplex::Full
+        } else {
+            Duplex::Half
+        };
+        LinkState {
+            up: true,
+            speed,
+            duplex,
+            flow_control: self.flow_control(),
+            // the crossover is informative, a PHY that cannot report it does not fail the query
+            mdi: self
+                .has_copper_phy()
+                .then(|| self.phy().mdi_status(self).ok())
+                .flatten(),
+        }
+    }
+
+    /// Selects the crossover of the copper pairs and restarts autonegotiation for it to apply.
+    pub fn set_mdi_mode(&self, mode: MdiMode) -> IgbResult {
+        if !self.has_copper_phy() {
+            return Err(IgbError::HardwareState(
+                "no copper phy to select the mdi on",
+            ));
+        }
+        self.phy().set_mdi_mode(self, mode)?;
+        debug!("mdi mode {:?}", mode);
+        self.restart_phy_autoneg()
+    }
+
+    /// Enables gigabit to 100 Mb/s downshift after `retries` failed gigabit attempts, or disables
+    /// it if `None`, and restarts autonegotiation for it to apply.
+    pub fn set_downshift(&self, retries: Option<u8>) -> IgbResult {
+        if retries.is_some_and(|retries| !(1..=MAX_DOWNSHIFT_RETRIES).contains(&retries)) {
+            return Err(IgbError::InvalidArgument(
+                "downshift retries not between 1 and 8",
+            ));
+        }
+        if !self.has_copper_phy() {
+            return Err(IgbError::HardwareState("no copper phy to downshift"));
+        }
+        self.phy().set_downshift(self, retries)?;
+        debug!("downshift after {:?} retries", retries);
+        self.restart_phy_autoneg()
+    }
+
+    /// Restarts autonegotiation unless the link is forced.
+    pub(crate) fn restart_phy_autoneg(&self) -> IgbResult {
+        if self.read_mdi(PHY_CONTROL)? & MII_CR_AUTO_NEG_EN == 0 {
+            return Ok(());
+        }
+        self.phy().restart_autoneg(self)
+    }
+
+    /// Whether the link is negotiated by a copper PHY, directly attached or behind SGMII.
+    pub(crate) fn has_copper_phy(&self) -> bool {
+        match self.regs().ctrl_ext.read_as_enum(CTRL_EXT::LINK_MODE) {
+            Some(CTRL_EXT::LINK_MODE::Value::DirectCopper) => true,
+            Some(CTRL_EXT::LINK_MODE::Value::Sgmii) => self.phy_id() != 0,
+            _ => false,
+        }
+    }
+
+    /// Starts the link of the port for its link mode, without waiting for it to come up: the
+    /// copper PHY or the SGMII PHY negotiates with the link partner, and fiber and backplane
+    /// ports negotiate 1000BASE-X. Flow control is resolved by the link poll once it is up.
+    pub(crate) fn start_link(&self) -> IgbResult {
+        match self.regs().ctrl_ext.read_as_enum(CTRL_EXT::LINK_MODE) {
+            Some(CTRL_EXT::LINK_MODE::Value::DirectCopper) => self.setup_link(),
+            Some(CTRL_EXT::LINK_MODE::Value::Sgmii) => self.sgmii_start_link(),
+            _ => {
+                self.serdes_start_link(true);
+                Ok(())
+            }
+        }
+    }
+
+    /// Starts the link of the port, picking the link mode from the module of SFP ports.
+    pub(crate) fn bring_up_link(&mut self) -> IgbResult {
+        if self.has_sfp_cage() {
+            self.sfp_start_link()
+        } else {
+            self.start_link()
+        }
+    }
+
+    /// Restarts autonegotiation with the current advertisement, through the copper PHY or the
+    /// PCS.
+    pub(crate) fn renegotiate_link(&self) -> IgbResult {
+        if self.has_copper_phy() {
+            self.setup_link()
+        } else {
+            self.pcs_setup_autoneg();
+            Ok(())
+        }
+    }
+
+    /// Returns the link modes the port can run.
+    pub fn supported_modes(&self) -> LinkModes {
+        if self.mac_type() == IgbMacType::I354 || !self.has_copper_phy() {
+            // backplane and 1000BASE-X links only run at 1000 Mb/s full duplex
+            LinkModes::SPEED_1000_FULL
+        } else {
+            LinkModes::COPPER
+        }
+    }
+
+    /// Returns the link modes advertised by autonegotiation.
+    pub fn advertised_modes(&self) -> LinkModes {
+        self.advertised & self.supported_modes()
+    }
+
+    /// Restricts the link modes advertised by autonegotiation to `modes`, and restarts it.
+    pub fn set_advertised_modes(&mut self, modes: LinkModes) -> IgbResult {
+        if modes.is_empty() {
+            return Err(IgbError::InvalidArgument("no link mode advertised"));
+        }
+        if !self.supported_modes().contains(modes) {
+            return Err(IgbError::InvalidArgument(
+                "link mode not supported by the port",
+            ));
+        }
+        self.advertised = modes;
+        info!("igb: advertising {:?}", modes);
+        self.renegotiate_link()
+    }
+
+    /// Returns the link modes announced by the link partner during the last autonegotiation.
+    pub fn partner_modes(&self) -> IgbResult<LinkModes> {
+        if !self.has_copper_phy() {
+            let lpab = self.regs().pcs_lpab.extract();
+            let mut modes = LinkModes::empty();
+            if lpab.is_set(PCS_AN::HD) {
+                modes = modes | LinkModes::SPEED_1000_HALF;
+            }
+            if lpab.is_set(PCS_AN::FD) {
+                modes = modes | LinkModes::SPEED_1000_FULL;
+            }
+            return Ok(modes);
+        }
+
+        let ability = self.read_mdi(PHY_LP_ABILITY)?;
+        let status_1000 = self.read_mdi(PHY_1000T_STATUS)?;
+        Ok([
+            (ability & NWAY_AR_10T_HD, LinkModes::SPEED_10_HALF),
+            (ability & NWAY_AR_10T_FD, LinkModes::SPEED_10_FULL),
+            (ability & NWAY_AR_100TX_HD, LinkModes::SPEED_100_HALF),
+            (ability & NWAY_AR_100TX_FD, LinkModes::SPEED_100_FULL),
+            (
+                status_1000 & SR_1000T_LP_HD_CAPS,
+                LinkModes::SPEED_1000_HALF,
+            ),
+            (
+                status_1000 & SR_1000T_LP_FD_CAPS,
+                LinkModes::SPEED_1000_FULL,
+            ),
+        ]
+        .into_iter()
+        .filter(|&(bit, _)| bit != 0)
+        .fold(LinkModes::empty(), |modes, (_, mode)| modes | mode))
+    }
+
+    /// Resolves flow control for a link that just came up, from whichever autonegotiation
+    /// brought it up.
+    pub(crate) fn resolve_flow_control(&self, duplex: Duplex) -> IgbResult {
+        if self.regs().ctrl.is_set(CTRL::FRCSPD) {
+            // forced by force_link, nothing was negotiated
+            return Ok(());
+        }
+        match self.regs().ctrl_ext.read_as_enum(CTRL_EXT::LINK_MODE) {
+            Some(CTRL_EXT::LINK_MODE::Value::DirectCopper) => {
+                self.config_fc_after_link_up(duplex)?;
+            }
+            Some(CTRL_EXT::LINK_MODE::Value::Sgmii) if self.phy_id() != 0 => {
+                self.config_fc_after_link_up(duplex)?;
+            }
+            _ if self.regs().pcs_lctl.is_set(PCS_LCTL::AN_ENABLE) => {
+                self.config_fc_after_pcs_link_up();
+            }
+            _ => self.force_flow_control(self.fc_requested),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+//! MAC and PHY loopback, looping transmitted frames back to the receive path without a link
+//! partner.
+
+use tock_registers::interfaces::ReadWriteable;
+
+use crate::hal::IgbHal;
+use crate::igb::Igb;
+use crate::mac::IgbPhyType;
+use crate::mdio::PHY_CONTROL;
+use crate::phy::{
+    MII_CR_AUTO_NEG_EN, MII_CR_FULL_DUPLEX, MII_CR_LOOPBACK, MII_CR_POWER_DOWN,
+    MII_CR_RESTART_AUTO_NEG, MII_CR_SPEED_SELECT_LSB, MII_CR_SPEED_SELECT_MSB,
+};
+use crate::regs::{CTRL, RCTL};
+use crate::{IgbError, IgbResult};
+
+/// Point where transmitted frames are looped back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Loopback {
+    /// Frames go out on the link.
+    None,
+    /// Frames are looped back inside the MAC, before reaching the PHY or the PCS.
+    Mac,
+    /// Frames are looped back by the copper PHY, exercising the MAC to PHY interface.
+    Phy,
+}
+
+impl<H: IgbHal> Igb<H> {
+    /// Loops transmitted frames back at `mode`, forcing the link up at 1000 Mb/s full duplex, or
+    /// restarts the normal link of the copper PHY or the PCS for [`Loopback::None`].
+    pub fn set_loopback(&self, mode: Loopback) -> IgbResult {
+        let regs = self.regs();
+        match mode {
+            Loopback::None => {
+                regs.rctl.modify(RCTL::LBM::Normal);
+                if self.has_copper_phy() {
+                    let control = self.read_mdi(PHY_CONTROL)?;
+                    self.write_mdi(PHY_CONTROL, control & !MII_CR_LOOPBACK)?;
+                }
+                // leave the speed and duplex to the link again
+                regs.ctrl.modify(CTRL::FRCSPD::CLEAR + CTRL::FRCDPLX::CLEAR);
+                info!("igb: loopback disabled");
+                return self.start_link();
+            }
+            Loopback::Mac => regs.rctl.modify(RCTL::LBM::Mac),
+            Loopback::Phy => {
+                if !self.has_copper_phy() {
+                    return Err(IgbError::HardwareState("no copper phy to loop back"));
+                }
+                regs.rctl.modify(RCTL::LBM::Normal);
+                // autonegotiation off, 1000 Mb/s full duplex
+                let control = self.read_mdi(PHY_CONTROL)?
+                    & !(MII_CR_AUTO_NEG_EN
+                        | MII_CR_RESTART_AUTO_NEG
+                        | MII_CR_POWER_DOWN
+                        | MII_CR_SPEED_SELECT_LSB);
+                self.write_mdi(
+                    PHY_CONTROL,
+                    control | MII_CR_LOOPBACK | MII_CR_SPEED_SELECT_MSB | MII_CR_FULL_DUPLEX,
+                )?;
+            }
+        }
+
+        regs.ctrl.modify(
+            CTRL::SLU::SET
+                + CTRL::LRST::CLEAR
+                + CTRL::ILOS::CLEAR
+                + CTRL::FRCSPD::SET
+                + CTRL::FRCDPLX::SET
+                + CTRL::SPEED::Speed1000
+                + CTRL::FD::SET,
+        );
+        if mode == Loopback::Phy && self.phy_type() == IgbPhyType::M88 {
+            // the external Marvell PHYs report no signal while looping back
+            regs.ctrl.modify(CTRL::ILOS::SET);
+        }
+        info!("igb: {:?} loopback enabled", mode);
+        Ok(())
+    }
+}
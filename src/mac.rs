@@ -0,0 +1,225 @@
+//! MAC family detection and per-family capabilities.
+
+use crate::constants::*;
+
+/// MAC family of an igb controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IgbMacType {
+    /// 82575EB and 82575GB.
+    Mac82575,
+    /// 82576.
+    Mac82576,
+    /// 82580 and the DH89xxCC integrated MACs.
+    Mac82580,
+    /// I350.
+    I350,
+    /// I354 (Atom C2000 integrated MAC).
+    I354,
+    /// I210.
+    I210,
+    /// I211.
+    I211,
+}
+
+/// PHY a MAC family is paired with on copper ports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IgbPhyType {
+    /// Intel IGP PHY integrated in the 82575 and 82576.
+    Igp,
+    /// External Marvell PHY.
+    M88,
+    /// Internal PHY of the 82580 and I350.
+    Internal82580,
+    /// Internal PHY of the I210 and I211.
+    InternalI210,
+}
+
+/// Non-volatile memory holding the MAC address and the init control words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IgbNvmType {
+    /// SPI EEPROM read through EERD.
+    SpiEeprom,
+    /// External flash exposed as a shadow RAM.
+    Flash,
+    /// Integrated one time programmable memory.
+    Invm,
+}
+
+/// Capabilities and quirks of a MAC family.
+#[derive(Debug, Clone, Copy)]
+pub struct MacCapabilities {
+    /// Number of receive queues.
+    pub rx_queues: u16,
+    /// Number of transmit queues.
+    pub tx_queues: u16,
+    /// Number of SR-IOV virtual functions.
+    pub vfs: u16,
+    /// Number of receive address registers.
+    pub rar_entries: u16,
+    /// Default receive packet buffer size in KB.
+    pub rx_packet_buffer_kb: u32,
+    /// PHY found on copper ports.
+    pub phy: IgbPhyType,
+    /// Non-volatile memory type.
+    pub nvm: IgbNvmType,
+    /// Queues only exist at the legacy 0x2800/0x3800 offsets with a 0x100 stride.
+    pub legacy_queue_regs: bool,
+    /// RXPBS holds a table index instead of a size in KB.
+    pub encoded_rxpbs: bool,
+    /// Energy Efficient Ethernet is supported.
+    pub eee: bool,
+}
+
+const CAPS_82575: MacCapabilities = MacCapabilities {
+    rx_queues: 4,
+    tx_queues: 4,
+    vfs: 0,
+    rar_entries: 16,
+    rx_packet_buffer_kb: 34,
+    phy: IgbPhyType::Igp,
+    nvm: IgbNvmType::SpiEeprom,
+    legacy_queue_regs: true,
+    encoded_rxpbs: false,
+    eee: false,
+};
+
+const CAPS_82576: MacCapabilities = MacCapabilities {
+    rx_queues: 16,
+    tx_queues: 16,
+    vfs: 7,
+    rar_entries: 24,
+    rx_packet_buffer_kb: 64,
+    phy: IgbPhyType::Igp,
+    nvm: IgbNvmType::SpiEeprom,
+    legacy_queue_regs: false,
+    encoded_rxpbs: false,
+    eee: false,
+};
+
+const CAPS_82580: MacCapabilities = MacCapabilities {
+    rx_queues: 8,
+    tx_queues: 8,
+    vfs: 0,
+    rar_entries: 24,
+    rx_packet_buffer_kb: 36,
+    phy: IgbPhyType::Internal82580,
+    nvm: IgbNvmType::SpiEeprom,
+    legacy_queue_regs: false,
+    encoded_rxpbs: true,
+    eee: false,
+};
+
+const CAPS_I350: MacCapabilities = MacCapabilities {
+    rx_queues: 8,
+    tx_queues: 8,
+    vfs: 7,
+    rar_entries: 32,
+    rx_packet_buffer_kb: 36,
+    phy: IgbPhyType::Internal82580,
+    nvm: IgbNvmType::SpiEeprom,
+    legacy_queue_regs: false,
+    encoded_rxpbs: true,
+    eee: true,
+};
+
+const CAPS_I354: MacCapabilities = MacCapabilities {
+    rx_queues: 8,
+    tx_queues: 8,
+    vfs: 7,
+    rar_entries: 32,
+    rx_packet_buffer_kb: 36,
+    phy: IgbPhyType::M88,
+    nvm: IgbNvmType::SpiEeprom,
+    legacy_queue_regs: false,
+    encoded_rxpbs: true,
+    eee: false,
+};
+
+const CAPS_I210: MacCapabilities = MacCapabilities {
+    rx_queues: 4,
+    tx_queues: 4,
+    vfs: 0,
+    rar_entries: 16,
+    rx_packet_buffer_kb: 34,
+    phy: IgbPhyType::InternalI210,
+    nvm: IgbNvmType::Flash,
+    legacy_queue_regs: false,
+    encoded_rxpbs: false,
+    eee: true,
+};
+
+const CAPS_I211: MacCapabilities = MacCapabilities {
+    rx_queues: 2,
+    tx_queues: 2,
+    vfs: 0,
+    rar_entries: 16,
+    rx_packet_buffer_kb: 34,
+    phy: IgbPhyType::InternalI210,
+    nvm: IgbNvmType::Invm,
+    legacy_queue_regs: false,
+    encoded_rxpbs: false,
+    eee: true,
+};
+
+impl IgbMacType {
+    /// Returns the MAC family of the device with PCI `device_id`, or `None` if it is not an igb
+    /// device.
+    pub fn from_device_id(device_id: u16) -> Option<Self> {
+        let mac_type = match device_id as u32 {
+            IGB_DEV_ID_82575EB_COPPER
+            | IGB_DEV_ID_82575EB_FIBER_SERDES
+            | IGB_DEV_ID_82575GB_QUAD_COPPER => Self::Mac82575,
+            IGB_DEV_ID_82576
+            | IGB_DEV_ID_82576_FIBER
+            | IGB_DEV_ID_82576_SERDES
+            | IGB_DEV_ID_82576_QUAD_COPPER
+            | IGB_DEV_ID_82576_QUAD_COPPER_ET2
+            | IGB_DEV_ID_82576_NS
+            | IGB_DEV_ID_82576_NS_SERDES
+            | IGB_DEV_ID_82576_SERDES_QUAD => Self::Mac82576,
+            IGB_DEV_ID_82580_COPPER
+            | IGB_DEV_ID_82580_FIBER
+            | IGB_DEV_ID_82580_SERDES
+            | IGB_DEV_ID_82580_SGMII
+            | IGB_DEV_ID_82580_COPPER_DUAL
+            | IGB_DEV_ID_82580_QUAD_FIBER
+            | IGB_DEV_ID_DH89XXCC_SGMII
+            | IGB_DEV_ID_DH89XXCC_SERDES
+            | IGB_DEV_ID_DH89XXCC_BACKPLANE
+            | IGB_DEV_ID_DH89XXCC_SFP => Self::Mac82580,
+            IGB_DEV_ID_I350_COPPER
+            | IGB_DEV_ID_I350_FIBER
+            | IGB_DEV_ID_I350_SERDES
+            | IGB_DEV_ID_I350_SGMII
+            | IGB_DEV_ID_I350_DA4 => Self::I350,
+            IGB_DEV_ID_I354_BACKPLANE_1GBPS
+            | IGB_DEV_ID_I354_SGMII
+            | IGB_DEV_ID_I354_BACKPLANE_2_5GBPS => Self::I354,
+            IGB_DEV_ID_I210_COPPER
+            | IGB_DEV_ID_I210_COPPER_OEM1
+            | IGB_DEV_ID_I210_COPPER_IT
+            | IGB_DEV_ID_I210_FIBER
+            | IGB_DEV_ID_I210_SERDES
+            | IGB_DEV_ID_I210_SGMII
+            | IGB_DEV_ID_I210_COPPER_FLASHLESS
+            | IGB_DEV_ID_I210_SERDES_FLASHLESS
+            | IGB_DEV_ID_I210_SGMII_FLASHLESS => Self::I210,
+            IGB_DEV_ID_I211_COPPER => Self::I211,
+            _ => return None,
+        };
+        Some(mac_type)
+    }
+
+    /// Returns the capabilities and quirks of this MAC family.
+    pub fn capabilities(self) -> &'static MacCapabilities {
+        match self {
+            Self::Mac82575 => &CAPS_82575,
+            Self::Mac82576 => &CAPS_82576,
+            Self::Mac82580 => &CAPS_82580,
+            Self::I350 => &CAPS_I350,
+            Self::I354 => &CAPS_I354,
+            Self::I210 => &CAPS_I210,
+            Self::I211 => &CAPS_I211,
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Clause 22 MDIO access to the PHY through MDIC, see section 8.2.4 of the datasheet.
+
+use core::time::Duration;
+
+use tock_registers::interfaces::{Readable, Writeable};
+
+use crate::hal::IgbHal;
+use crate::i2c::MAX_I2C_PHY_ADDR;
+use crate::igb::Igb;
+use crate::mac::IgbPhyType;
+use crate::regs::MDIC;
+use crate::semaphore::SwFwResource;
+use crate::{IgbError, IgbResult};
+
+/// Deadline for an MDIC transaction.
+const MDIC_TIMEOUT: Duration = Duration::from_millis(10);
+/// Highest clause 22 register address.
+const MAX_PHY_REG: u32 = 0x1F;
+/// Highest clause 22 PHY address.
+const MAX_PHY_ADDR: u32 = 0x1F;
+/// PHY address used until a PHY has been found, the one of the internal PHYs.
+pub(crate) const DEFAULT_PHY_ADDR: u32 = 1;
+
+/// MII Control.
+pub const PHY_CONTROL: u32 = 0x00;
+/// MII Status.
+pub const PHY_STATUS: u32 = 0x01;
+/// PHY Identifier 1, OUI bits 3 to 18.
+pub const PHY_ID1: u32 = 0x02;
+/// PHY Identifier 2, OUI bits 19 to 24, model and revision.
+pub const PHY_ID2: u32 = 0x03;
+/// Autonegotiation Advertisement.
+pub const PHY_AUTONEG_ADV: u32 = 0x04;
+/// Link Partner Ability.
+pub const PHY_LP_ABILITY: u32 = 0x05;
+/// 1000BASE-T Control.
+pub const PHY_1000T_CTRL: u32 = 0x09;
+/// 1000BASE-T Status.
+pub const PHY_1000T_STATUS: u32 = 0x0A;
+/// MMD access control, selecting the device and the function of the MMD access data register.
+const PHY_MMD_ACCESS_CTRL: u32 = 0x0D;
+/// MMD access address or data.
+const PHY_MMD_ACCESS_DATA: u32 = 0x0E;
+/// MMD access control function reading or writing the data at the latched address.
+const MMD_FUNCTION_DATA: u16 = 1 << 14;
+/// Highest MMD device address.
+const MAX_MMD_DEVICE: u8 = 0x1F;
+/// Extended management interface address register of the 82580 and I350 internal PHY.
+const EMI_ADDR: u32 = 0x10;
+/// Extended management interface data register.
+const EMI_DATA: u32 = 0x11;
+
+/// Page select register of the Marvell and Intel internal PHYs.
+const PHY_PAGE_SELECT: u32 = 0x16;
+/// Page select register of the IGP PHYs, taking the page shifted by 5.
+const IGP_PHY_PAGE_SELECT: u32 = 0x1F;
+
+impl<H: IgbHal> Igb<H> {
+    /// Runs a single MDIC transaction with the PHY semaphore already held.
+    fn mdic(&self, phy_addr: u32, reg_addr: u32, write: Option<u16>) -> IgbResult<u16> {
+        let mdic = &self.regs().mdic;
+        let op = match write {
+            Some(value) => MDIC::DATA.val(value as u32) + MDIC::OP::Write,
+            None => MDIC::OP::Read,
+        };
+        mdic.write(op + MDIC::REGADD.val(reg_addr) + MDIC::PHYADD.val(phy_addr));
+
+        let value = Self::poll("mdi transaction", MDIC_TIMEOUT, || {
+            let value = mdic.extract();
+            value.is_set(MDIC::READY).then_some(value)
+        })?;
+        if value.is_set(MDIC::E) {
+            debug!("mdi error: phy {}, reg {}", phy_addr, reg_addr);
+            return Err(IgbError::HardwareState(
+                "phy did not answer the mdi transaction",
+            ));
+        }
+        Ok(value.read(MDIC::DATA) as u16)
+    }
+
+    /// Runs a single PHY register access on the bus the PHY sits on, with the PHY semaphore
+    /// already held.
+    fn phy_transfer(&self, phy_addr: u32, reg_addr: u32, write: Option<u16>) -> IgbResult<u16> {
+        if self.phy_over_i2c() {
+            self.i2ccmd_phy(phy_addr, reg_addr, write)
+        } else {
+            self.mdic(phy_addr, reg_addr, write)
+        }
+    }
+
+    /// Reads a PHY register through MDIC, or I2CCMD for SGMII PHYs on the I2C bus.
+    pub fn read_mdi(&self, reg_addr: u32) -> IgbResult<u16> {
+        if reg_addr > MAX_PHY_REG {
+            return Err(IgbError::InvalidArgument("phy register address above 31"));
+        }
+        let _phy = self.acquire_swfw(SwFwResource::Phy)?;
+        self.phy_transfer(self.phy_addr(), reg_addr, None)
+    }
+
+    /// Writes a PHY register through MDIC, or I2CCMD for SGMII PHYs on the I2C bus.
+    pub fn write_mdi(&self, reg_addr: u32, value: u16) -> IgbResult {
+        if reg_addr > MAX_PHY_REG {
+            return Err(IgbError::InvalidArgument("phy register address above 31"));
+        }
+        let _phy = self.acquire_swfw(SwFwResource::Phy)?;
+        self.phy_transfer(self.phy_addr(), reg_addr, Some(value))?;
+        Ok(())
+    }
+
+    /// Selects `page` through the page select register of this PHY.
+    fn select_phy_page(&self, page: u16) -> IgbResult {
+        let (reg, value) = match self.phy_type() {
+            IgbPhyType::Igp => (IGP_PHY_PAGE_SELECT, page << 5),
+            _ => (PHY_PAGE_SELECT, page),
+        };
+        self.phy_transfer(self.phy_addr(), reg, Some(value))?;
+        Ok(())
+    }
+
+    /// Reads, or writes if `write` is set, register `reg_addr` of `page`, switching back to page 0
+    /// afterwards.
+    fn paged_mdi(&self, page: u16, reg_addr: u32, write: Option<u16>) -> IgbResult<u16> {
+        if reg_addr > MAX_PHY_REG {
+            return Err(IgbError::InvalidArgument("phy register address above 31"));
+        }
+        let _phy = self.acquire_swfw(SwFwResource::Phy)?;
+        self.select_phy_page(page)?;
+        let result = self.phy_transfer(self.phy_addr(), reg_addr, write);
+        self.select_phy_page(0)?;
+        result
+    }
+
+    /// Reads register `reg_addr` of PHY page `page`.
+    pub fn read_mdi_paged(&self, page: u16, reg_addr: u32) -> IgbResult<u16> {
+        self.paged_mdi(page, reg_addr, None)
+    }
+
+    /// Writes register `reg_addr` of PHY page `page`.
+    pub fn write_mdi_paged(&self, page: u16, reg_addr: u32, value: u16) -> IgbResult {
+        self.paged_mdi(page, reg_addr, Some(value))?;
+        Ok(())
+    }
+
+    /// Reads register `reg_addr` of MMD `device` through the clause 22 MMD access registers.
+    pub fn read_mmd(&self, device: u8, reg_addr: u16) -> IgbResult<u16> {
+        if device > MAX_MMD_DEVICE {
+            return Err(IgbError::InvalidArgument("mmd device address above 31"));
+        }
+        let _phy = self.acquire_swfw(SwFwResource::Phy)?;
+        let phy_addr = self.phy_addr();
+        self.phy_transfer(phy_addr, PHY_MMD_ACCESS_CTRL, Some(device as u16))?;
+        self.phy_transfer(phy_addr, PHY_MMD_ACCESS_DATA, Some(reg_addr))?;
+        self.phy_transfer(
+            phy_addr,
+            PHY_MMD_ACCESS_CTRL,
+            Some(MMD_FUNCTION_DATA | device as u16),
+        )?;
+        let value = self.phy_transfer(phy_addr, PHY_MMD_ACCESS_DATA, None);
+        // leave the access registers in address mode
+        self.phy_transfer(phy_addr, PHY_MMD_ACCESS_CTRL, Some(0))?;
+        value
+    }
+
+    /// Reads register `emi_addr` of the extended management interface of the 82580 and I350
+    /// internal PHY.
+    pub(crate) fn read_emi(&self, emi_addr: u16) -> IgbResult<u16> {
+        let _phy = self.acquire_swfw(SwFwResource::Phy)?;
+        self.phy_transfer(self.phy_addr(), EMI_ADDR, Some(emi_addr))?;
+        self.phy_transfer(self.phy_addr(), EMI_DATA, None)
+    }
+
+    /// Returns the 32-bit identifier of the PHY at `phy_addr`, from MII registers 2 and 3.
+    fn read_phy_id_at(&self, phy_addr: u32) -> IgbResult<u32> {
+        let _phy = self.acquire_swfw(SwFwResource::Phy)?;
+        let id1 = self.phy_transfer(phy_addr, PHY_ID1, None)?;
+        let id2 = self.phy_transfer(phy_addr, PHY_ID2, None)?;
+        Ok((id1 as u32) << 16 | id2 as u32)
+    }
+
+    /// Returns the 32-bit identifier of the PHY, from MII registers 2 and 3.
+    pub fn read_phy_id(&self) -> IgbResult<u32> {
+        self.read_phy_id_at(self.phy_addr())
+    }
+
+    /// Looks for the PHY on the MDIO or I2C bus, trying the address configured in MDICNFG or the
+    /// default one first, and returns its address and identifier.
+    pub(crate) fn scan_phy(&self) -> IgbResult<(u32, u32)> {
+        let (first, last) = if self.phy_over_i2c() {
+            (1, MAX_I2C_PHY_ADDR)
+        } else {
+            (0, MAX_PHY_ADDR)
+        };
+        let preferred = self.mdicnfg_phy_addr().unwrap_or(DEFAULT_PHY_ADDR);
+        let addrs =
+            core::iter::once(preferred).chain((first..=last).filter(|&addr| addr != preferred));
+        for addr in addrs {
+            match self.read_phy_id_at(addr) {
+                Ok(id) if id != 0 && id != u32::MAX => {
+                    debug!("phy {:#010x} found at address {}", id, addr);
+                    return Ok((addr, id));
+                }
+                Err(e @ IgbError::SemaphoreTimeout(_)) => return Err(e),
+                _ => {}
+            }
+        }
+        Err(IgbError::HardwareState(
+            "no phy found on the mdio or i2c bus",
+        ))
+    }
+}
@@ -42,7 +42,7 @@ impl MemPool {
             x => x,
         };
 
-        if HUGE_PAGE_SIZE % entry_size != 0 {
+        if !HUGE_PAGE_SIZE.is_multiple_of(entry_size) {
             error!("entry size must be a divisor of the page size");
             return Err(IgbError::PageNotAligned);
         }
@@ -78,6 +78,18 @@ impl MemPool {
         self.free_stack.borrow_mut().pop()
     }
 
+    /// Takes the free buffer `id` out of the memory pool, returning `false` if it is in use.
+    pub(crate) fn claim_buf(&self, id: usize) -> bool {
+        let mut free_stack = self.free_stack.borrow_mut();
+        match free_stack.iter().position(|&free| free == id) {
+            Some(index) => {
+                free_stack.swap_remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Marks a buffer in the memory pool as free.
     pub(crate) fn free_buf(&self, id: usize) {
         assert!(
@@ -87,13 +99,18 @@ impl MemPool {
         );
 
         let mut free_stack = self.free_stack.borrow_mut();
-        if free_stack.iter().any(|&x| x == id) {
+        if free_stack.contains(&id) {
             panic!("free buf: buffer already free");
         }
 
         free_stack.push(id);
     }
 
+    /// Return the number of entries.
+    pub fn num_entries(&self) -> usize {
+        self.num_entries
+    }
+
     /// Return entry size.
     pub fn entry_size(&self) -> usize {
         self.entry_size
@@ -0,0 +1,306 @@
+//! Access to the NVM (EEPROM or flash shadow RAM) through the EERD and EEWR registers, and to the
+//! iNVM of the I211 and flashless I210.
+
+use core::time::Duration;
+
+use tock_registers::interfaces::{ReadWriteable, Readable, Writeable};
+use tock_registers::registers::ReadWrite;
+
+use crate::constants::{
+    IGB_DEV_ID_I210_COPPER_FLASHLESS, IGB_DEV_ID_I210_SERDES_FLASHLESS,
+    IGB_DEV_ID_I210_SGMII_FLASHLESS,
+};
+use crate::hal::IgbHal;
+use crate::igb::Igb;
+use crate::mac::{IgbMacType, IgbNvmType};
+use crate::regs::{EEC, EERD, EEWR, INVM_DATA, STATUS};
+use crate::semaphore::SwFwResource;
+use crate::{IgbError, IgbResult};
+
+/// Deadline for a single EERD word read.
+const NVM_READ_TIMEOUT: Duration = Duration::from_millis(10);
+/// Deadline for a single EEWR word write.
+const NVM_WRITE_TIMEOUT: Duration = Duration::from_millis(20);
+/// Deadline for the I210 shadow RAM to be committed to flash.
+const NVM_FLASH_UPDATE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Word offset of the Ethernet address.
+pub const NVM_MAC_ADDR: u16 = 0x00;
+/// Word offset of the first half of the PBA number.
+pub const NVM_PBA_0: u16 = 0x08;
+/// Word offset of the second half of the PBA number.
+pub const NVM_PBA_1: u16 = 0x09;
+/// Word offset of Initialization Control Word 1.
+pub const NVM_INIT_CTRL_1: u16 = 0x0A;
+/// Word offset of Initialization Control Word 2.
+pub const NVM_INIT_CTRL_2: u16 = 0x0F;
+/// Word offset of Initialization Control Word 3 for the first port.
+pub const NVM_INIT_CTRL_3: u16 = 0x24;
+/// Word offset of Initialization Control Word 3 for the second port of the 82575 and 82576.
+pub const NVM_INIT_CTRL_3_PORT_B: u16 = 0x14;
+/// Word offset of the checksum, the last word of the checksummed section.
+pub const NVM_CHECKSUM_REG: u16 = 0x3F;
+/// Value the words of a section must sum up to.
+pub const NVM_SUM: u16 = 0xBABA;
+/// Marker in [`NVM_PBA_0`] for a PBA stored as a string pointed to by [`NVM_PBA_1`].
+const NVM_PBA_PTR_GUARD: u16 = 0xFAFA;
+/// Number of words addressable through EERD.
+const NVM_WORDS: usize = 1 << 14;
+/// Word offset of Initialization Control Word 4.
+pub const NVM_INIT_CTRL_4: u16 = 0x13;
+/// Word offset of the LED 1 configuration.
+pub const NVM_LED_1_CFG: u16 = 0x1C;
+/// Word offset of the LED 0 and 2 configuration.
+pub const NVM_LED_0_2_CFG: u16 = 0x1F;
+
+/// Words the I211 falls back to when they are not programmed in the iNVM.
+const INVM_DEFAULTS: [(u16, u16); 4] = [
+    (NVM_INIT_CTRL_2, 0x7243),
+    (NVM_INIT_CTRL_4, 0x00C1),
+    (NVM_LED_1_CFG, 0x0184),
+    (NVM_LED_0_2_CFG, 0x200C),
+];
+/// Dwords following a CSR autoload record header.
+const INVM_CSR_AUTOLOAD_DWORDS: usize = 1;
+/// Dwords following an RSA key SHA-256 record header.
+const INVM_RSA_KEY_SHA256_DWORDS: usize = 8;
+
+impl<H: IgbHal> Igb<H> {
+    /// Returns the kind of NVM fitted to this device, telling a flashless I210 apart from one
+    /// with an external flash.
+    pub fn nvm_type(&self) -> IgbNvmType {
+        let nvm = self.capabilities().nvm;
+        if nvm != IgbNvmType::Flash {
+            return nvm;
+        }
+        match self.device_id() as u32 {
+            IGB_DEV_ID_I210_COPPER_FLASHLESS
+            | IGB_DEV_ID_I210_SERDES_FLASHLESS
+            | IGB_DEV_ID_I210_SGMII_FLASHLESS => IgbNvmType::Invm,
+            _ if !self.regs().eec.is_set(EEC::FLASH_DETECTED) => IgbNvmType::Invm,
+            _ => IgbNvmType::Flash,
+        }
+    }
+
+    /// Reads the NVM word at `offset`, from the iNVM on devices without an EEPROM or flash.
+    pub fn read_nvm(&self, offset: u16) -> IgbResult<u16> {
+        if offset as usize >= NVM_WORDS {
+            return Err(IgbError::InvalidArgument("nvm offset out of range"));
+        }
+
+        let _nvm = self.acquire_swfw(SwFwResource::Nvm)?;
+        self.read_nvm_locked(offset)
+    }
+
+    /// Reads the NVM word at `offset` with the NVM semaphore already held.
+    fn read_nvm_locked(&self, offset: u16) -> IgbResult<u16> {
+        match self.nvm_type() {
+            IgbNvmType::Invm => self.read_invm(offset),
+            _ => self.read_eerd(offset),
+        }
+    }
+
+    /// Reads the NVM word at `offset` through EERD.
+    fn read_eerd(&self, offset: u16) -> IgbResult<u16> {
+        let regs = self.regs();
+        regs.eerd
+            .write(EERD::ADDR.val(offset as u32) + EERD::START::SET);
+        let eerd = Self::poll("nvm read", NVM_READ_TIMEOUT, || {
+            let eerd = regs.eerd.extract();
+            eerd.is_set(EERD::DONE).then_some(eerd)
+        })?;
+        Ok(eerd.read(EERD::DATA) as u16)
+    }
+
+    /// Reads `data.len()` consecutive NVM words starting at `offset`.
+    pub fn read_nvm_words(&self, offset: u16, data: &mut [u16]) -> IgbResult {
+        if offset as usize + data.len() > NVM_WORDS {
+            return Err(IgbError::InvalidArgument("nvm range out of bounds"));
+        }
+        let _nvm = self.acquire_swfw(SwFwResource::Nvm)?;
+        for (i, word) in data.iter_mut().enumerate() {
+            *word = self.read_nvm_locked(offset + i as u16)?;
+        }
+        Ok(())
+    }
+
+    /// Looks `offset` up in the word autoload records of the iNVM, falling back to the I211
+    /// defaults for the words the factory does not program.
+    fn read_invm(&self, offset: u16) -> IgbResult<u16> {
+        let invm = &self.regs().invm_data;
+        let mut i = 0;
+        while i < invm.len() {
+            let dword = invm[i].extract();
+            match dword.read_as_enum(INVM_DATA::RECORD_TYPE) {
+                Some(INVM_DATA::RECORD_TYPE::Value::Uninitialized) => break,
+                Some(INVM_DATA::RECORD_TYPE::Value::WordAutoload)
+                    if dword.read(INVM_DATA::WORD_ADDRESS) == offset as u32 =>
+                {
+                    return Ok(dword.read(INVM_DATA::WORD_DATA) as u16);
+                }
+                Some(INVM_DATA::RECORD_TYPE::Value::CsrAutoload) => i += INVM_CSR_AUTOLOAD_DWORDS,
+                Some(INVM_DATA::RECORD_TYPE::Value::RsaKeySha256) => {
+                    i += INVM_RSA_KEY_SHA256_DWORDS
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+
+        INVM_DEFAULTS
+            .iter()
+            .find(|&&(word, _)| word == offset)
+            .map(|&(_, data)| data)
+            .ok_or_else(|| {
+                debug!("nvm word {:#x} not found in iNVM", offset);
+                IgbError::HardwareState("word not programmed in iNVM")
+            })
+    }
+
+    /// Returns the register words are written through: EEWR, or SRWR for the I210 shadow RAM.
+    fn nvm_write_reg(&self) -> &ReadWrite<u32, EEWR::Register> {
+        match self.nvm_type() {
+            IgbNvmType::Flash => &self.regs().srwr,
+            _ => &self.regs().eewr,
+        }
+    }
+
+    /// Writes a single NVM word without updating the checksum.
+    fn write_nvm_word(&self, offset: u16, data: u16) -> IgbResult {
+        let reg = self.nvm_write_reg();
+        reg.write(EEWR::ADDR.val(offset as u32) + EEWR::DATA.val(data as u32) + EEWR::START::SET);
+        Self::poll("nvm write", NVM_WRITE_TIMEOUT, || {
+            reg.is_set(EEWR::DONE).then_some(())
+        })
+    }
+
+    /// Writes `data` at `offset` and updates the checksum of this port's section.
+    pub fn write_nvm(&self, offset: u16, data: &[u16]) -> IgbResult {
+        if self.nvm_type() == IgbNvmType::Invm {
+            return Err(IgbError::InvalidArgument("iNVM is read only"));
+        }
+        if offset as usize + data.len() > NVM_WORDS {
+            return Err(IgbError::InvalidArgument("nvm range out of bounds"));
+        }
+
+        let _nvm = self.acquire_swfw(SwFwResource::Nvm)?;
+        for (i, &word) in data.iter().enumerate() {
+            self.write_nvm_word(offset + i as u16, word)?;
+        }
+        debug!("wrote {} nvm words at {:#x}", data.len(), offset);
+
+        self.update_nvm_checksum_locked()
+    }
+
+    /// Returns the word offset of the NVM section of this LAN port.
+    pub(crate) fn nvm_lan_offset(&self) -> u16 {
+        let lan_id = self.regs().status.read(STATUS::LAN_ID) as u16;
+        match self.mac_type() {
+            IgbMacType::Mac82580 | IgbMacType::I350 | IgbMacType::I354 if lan_id != 0 => {
+                0x40 + 0x40 * lan_id
+            }
+            _ => 0,
+        }
+    }
+
+    /// Sums the words of this port's section, checksum word excluded.
+    fn nvm_section_sum(&self) -> IgbResult<u16> {
+        let base = self.nvm_lan_offset();
+        let mut sum = 0u16;
+        for offset in base..base + NVM_CHECKSUM_REG {
+            sum = sum.wrapping_add(self.read_nvm_locked(offset)?);
+        }
+        Ok(sum)
+    }
+
+    /// Checks that the words of this port's section sum up to [`NVM_SUM`].
+    pub fn validate_nvm_checksum(&self) -> IgbResult {
+        let _nvm = self.acquire_swfw(SwFwResource::Nvm)?;
+        let checksum = self.read_nvm_locked(self.nvm_lan_offset() + NVM_CHECKSUM_REG)?;
+        let sum = self.nvm_section_sum()?.wrapping_add(checksum);
+        if sum != NVM_SUM {
+            error!(
+                "nvm checksum invalid: sum {:#06x}, expected {:#06x}",
+                sum, NVM_SUM
+            );
+            return Err(IgbError::NvmChecksum(sum));
+        }
+        debug!("nvm checksum valid");
+        Ok(())
+    }
+
+    /// Rewrites the checksum word of this port's section, committing the shadow RAM to flash on
+    /// the I210.
+    pub fn update_nvm_checksum(&self) -> IgbResult {
+        let _nvm = self.acquire_swfw(SwFwResource::Nvm)?;
+        self.update_nvm_checksum_locked()
+    }
+
+    /// Rewrites the checksum word with the NVM semaphore already held.
+    fn update_nvm_checksum_locked(&self) -> IgbResult {
+        let checksum = NVM_SUM.wrapping_sub(self.nvm_section_sum()?);
+        self.write_nvm_word(self.nvm_lan_offset() + NVM_CHECKSUM_REG, checksum)?;
+
+        if self.nvm_type() == IgbNvmType::Flash {
+            let eec = &self.regs().eec;
+            eec.modify(EEC::FLUPD::SET);
+            Self::poll("flash update", NVM_FLASH_UPDATE_TIMEOUT, || {
+                eec.is_set(EEC::FLUDONE).then_some(())
+            })?;
+        }
+
+        debug!("nvm checksum updated to {:#06x}", checksum);
+        Ok(())
+    }
+
+    /// Reads the permanent Ethernet address of this port from the NVM.
+    pub fn read_nvm_mac_addr(&self) -> IgbResult<[u8; 6]> {
+        let mut words = [0u16; 3];
+        self.read_nvm_words(self.nvm_lan_offset() + NVM_MAC_ADDR, &mut words)?;
+        let mut addr = [0u8; 6];
+        for (i, word) in words.iter().enumerate() {
+            let bytes = word.to_le_bytes();
+            addr[2 * i] = bytes[0];
+            addr[2 * i + 1] = bytes[1];
+        }
+
+        // the second port of the 82575 and 82576 shares the address of the first one with the
+        // least significant bit flipped
+        if matches!(self.mac_type(), IgbMacType::Mac82575 | IgbMacType::Mac82576)
+            && self.regs().status.read(STATUS::LAN_ID) & 1 == 1
+        {
+            addr[5] ^= 1;
+        }
+
+        Ok(addr)
+    }
+
+    /// Reads the Printed Board Assembly number.
+    pub fn read_pba_num(&self) -> IgbResult<u32> {
+        let high = self.read_nvm(NVM_PBA_0)?;
+        if high == NVM_PBA_PTR_GUARD {
+            return Err(IgbError::HardwareState(
+                "pba number stored in string format",
+            ));
+        }
+        let low = self.read_nvm(NVM_PBA_1)?;
+        Ok((high as u32) << 16 | low as u32)
+    }
+
+    /// Reads Initialization Control Words 1, 2 and 3 of this port.
+    pub fn read_init_control_words(&self) -> IgbResult<[u16; 3]> {
+        let ctrl3 = match self.mac_type() {
+            IgbMacType::Mac82575 | IgbMacType::Mac82576
+                if self.regs().status.read(STATUS::LAN_ID) & 1 == 1 =>
+            {
+                NVM_INIT_CTRL_3_PORT_B
+            }
+            _ => self.nvm_lan_offset() + NVM_INIT_CTRL_3,
+        };
+        Ok([
+            self.read_nvm(NVM_INIT_CTRL_1)?,
+            self.read_nvm(NVM_INIT_CTRL_2)?,
+            self.read_nvm(ctrl3)?,
+        ])
+    }
+}
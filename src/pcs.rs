@@ -0,0 +1,202 @@
+//! Link setup of the ports whose MAC talks to the link partner or to an external PHY through the
+//! internal PCS, see section 3.7.4 of the datasheet.
+
+use core::time::Duration;
+
+use tock_registers::interfaces::{ReadWriteable, Readable, Writeable};
+
+use crate::hal::IgbHal;
+use crate::igb::Igb;
+use crate::link::LinkModes;
+use crate::mac::IgbMacType;
+use crate::phy::{Duplex, LinkSpeed};
+use crate::regs::{CTRL, CTRL_EXT, PCS_AN, PCS_CFG, PCS_LCTL, PCS_LSTAT};
+use crate::IgbResult;
+
+/// Deadline for the PCS to report the link as up.
+const PCS_LINK_TIMEOUT: Duration = Duration::from_secs(1);
+/// Deadline for the PCS to report the link as up, including the copper autonegotiation of the
+/// SGMII PHY.
+const SGMII_LINK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Interval between two checks of PCS_LSTAT.
+const PCS_LINK_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+impl<H: IgbHal> Igb<H> {
+    /// Returns the speed and duplex reported by the PCS, or `None` while the link is down.
+    pub fn pcs_link_status(&self) -> Option<(LinkSpeed, Duplex)> {
+        let lstat = self.regs().pcs_lstat.extract();
+        if !lstat.is_set(PCS_LSTAT::LINK_OK) {
+            return None;
+        }
+        let speed = match lstat.read_as_enum(PCS_LSTAT::SPEED) {
+            Some(PCS_LSTAT::SPEED::Value::Speed10) => LinkSpeed::Speed10,
+            Some(PCS_LSTAT::SPEED::Value::Speed100) => LinkSpeed::Speed100,
+            _ => LinkSpeed::Speed1000,
+        };
+        let duplex = if lstat.is_set(PCS_LSTAT::DUPLEX) {
+            Duplex::Full
+        } else {
+            Duplex::Half
+        };
+        Some((speed, duplex))
+    }
+
+    /// Waits for the PCS to report the link as up.
+    fn wait_pcs_link(&self) -> IgbResult<(LinkSpeed, Duplex)> {
+        Self::poll_every("pcs link", PCS_LINK_POLL_INTERVAL, PCS_LINK_TIMEOUT, || {
+            self.pcs_link_status()
+        })
+    }
+
+    /// Resolves flow control from the 1000BASE-X pause bits exchanged by the PCS and applies it
+    /// to the MAC.
+    pub(crate) fn config_fc_after_pcs_link_up(&self) {
+        let bits = |reg: u32| (PCS_AN::PAUSE.is_set(reg), PCS_AN::ASM_DIR.is_set(reg));
+        let local = bits(self.regs().pcs_anadv.get());
+        let partner = bits(self.regs().pcs_lpab.get());
+        self.force_flow_control(self.fc_requested.resolve(local, partner));
+    }
+
+    /// Advertises the requested 1000BASE-X duplex modes and pause bits, then restarts the
+    /// autonegotiation of the PCS.
+    pub(crate) fn pcs_setup_autoneg(&self) {
+        let regs = self.regs();
+        let modes = self.advertised_modes();
+        let (pause, asm_dir) = self.fc_requested.advertisement();
+        regs.pcs_anadv.modify(
+            PCS_AN::FD.val(modes.contains(LinkModes::SPEED_1000_FULL) as u32)
+                + PCS_AN::HD.val(modes.contains(LinkModes::SPEED_1000_HALF) as u32)
+                + PCS_AN::PAUSE.val(pause as u32)
+                + PCS_AN::ASM_DIR.val(asm_dir as u32),
+        );
+        regs.pcs_lctl.modify(
+            PCS_LCTL::FSD::CLEAR
+                + PCS_LCTL::FORCE_LINK::CLEAR
+                + PCS_LCTL::FLV::CLEAR
+                + PCS_LCTL::FORCE_FCTRL::CLEAR
+                + PCS_LCTL::AN_ENABLE::SET
+                + PCS_LCTL::AN_RESTART::SET,
+        );
+        debug!("1000base-x autonegotiation restarted");
+    }
+
+    /// Configures a fiber or backplane port for 1000BASE-X, negotiated with the link partner if
+    /// `autoneg` is set and forced to 1000 Mb/s full duplex otherwise, without waiting for the
+    /// link.
+    pub(crate) fn serdes_start_link(&self, autoneg: bool) {
+        let regs = self.regs();
+
+        // keep 1000BASE-KX on backplane ports, everything else is 1000BASE-X SerDes
+        if regs.ctrl_ext.read_as_enum(CTRL_EXT::LINK_MODE) != Some(CTRL_EXT::LINK_MODE::Value::Kx) {
+            regs.ctrl_ext.modify(CTRL_EXT::LINK_MODE::SerDes);
+        }
+        // power on the SFP cage, if any
+        regs.ctrl_ext.modify(CTRL_EXT::SDP3_DATA::CLEAR);
+        debug!("link mode {:?}", regs.ctrl_ext.read(CTRL_EXT::LINK_MODE));
+
+        regs.ctrl
+            .modify(CTRL::SLU::SET + CTRL::LRST::CLEAR + CTRL::ILOS::CLEAR);
+        if matches!(self.mac_type(), IgbMacType::Mac82575 | IgbMacType::Mac82576) {
+            // both software defined pins must be high for the optics to be enabled
+            regs.ctrl
+                .modify(CTRL::SDP0_DATA::SET + CTRL::SDP1_DATA::SET);
+        }
+        self.setup_flow_control();
+
+        regs.pcs_cfg0.write(PCS_CFG::PCS_EN::SET);
+
+        if autoneg {
+            self.pcs_setup_autoneg();
+        } else {
+            regs.pcs_lctl.modify(
+                PCS_LCTL::AN_ENABLE::CLEAR
+                    + PCS_LCTL::AN_RESTART::CLEAR
+                    + PCS_LCTL::FSV::Speed1000
+                    + PCS_LCTL::FDV::SET
+                    + PCS_LCTL::FSD::SET
+                    + PCS_LCTL::FORCE_LINK::SET
+                    + PCS_LCTL::FLV::SET
+                    + PCS_LCTL::FORCE_FCTRL::SET,
+            );
+            debug!("1000base-x forced to 1000 Mb/s full duplex");
+        }
+    }
+
+    /// Sets up the link of a fiber or backplane port, negotiating 1000BASE-X with the link
+    /// partner if `autoneg` is set and forcing 1000 Mb/s full duplex otherwise, and returns the
+    /// resulting speed and duplex.
+    pub fn serdes_link_setup(&self, autoneg: bool) -> IgbResult<(LinkSpeed, Duplex)> {
+        self.serdes_start_link(autoneg);
+
+        let (speed, duplex) = self.wait_pcs_link()?;
+        if autoneg {
+            self.config_fc_after_pcs_link_up();
+        } else {
+            self.force_flow_control(self.fc_requested);
+        }
+        info!(
+            "igb: serdes link up, {} Mb/s {:?} duplex, flow control {:?}",
+            speed.mbps(),
+            duplex,
+            self.flow_control()
+        );
+        Ok((speed, duplex))
+    }
+
+    /// Configures an SGMII port and starts the negotiations of the PCS with the external PHY and
+    /// of the PHY with the link partner, without waiting for the link.
+    pub(crate) fn sgmii_start_link(&self) -> IgbResult {
+        let regs = self.regs();
+
+        regs.ctrl_ext.modify(CTRL_EXT::LINK_MODE::Sgmii);
+        regs.ctrl
+            .modify(CTRL::SLU::SET + CTRL::LRST::CLEAR + CTRL::ILOS::CLEAR);
+        self.setup_flow_control();
+
+        regs.pcs_cfg0.write(PCS_CFG::PCS_EN::SET);
+        // SGMII autonegotiation only carries the speed and duplex, pause is resolved by the PHY
+        regs.pcs_lctl.modify(
+            PCS_LCTL::FSD::CLEAR
+                + PCS_LCTL::FORCE_LINK::CLEAR
+                + PCS_LCTL::FLV::CLEAR
+                + PCS_LCTL::FORCE_FCTRL::CLEAR
+                + PCS_LCTL::AN_TIMEOUT::CLEAR
+                + PCS_LCTL::AN_ENABLE::SET
+                + PCS_LCTL::AN_RESTART::SET,
+        );
+        debug!(
+            "sgmii autonegotiation restarted, phy on {}",
+            if self.phy_over_i2c() { "i2c" } else { "mdio" }
+        );
+
+        // a PHY-less SGMII link, such as a backplane, has nothing to negotiate on the copper side
+        if self.phy_id() != 0 {
+            self.phy_setup_autoneg()?;
+        }
+        Ok(())
+    }
+
+    /// Sets up the link of an SGMII port: the PCS negotiates the speed and duplex of the MAC with
+    /// the external PHY, which negotiates the link with the partner. Returns the speed and duplex
+    /// reported by the PCS.
+    pub fn sgmii_link_setup(&self) -> IgbResult<(LinkSpeed, Duplex)> {
+        self.sgmii_start_link()?;
+
+        let (speed, duplex) = Self::poll_every(
+            "sgmii link",
+            PCS_LINK_POLL_INTERVAL,
+            SGMII_LINK_TIMEOUT,
+            || self.pcs_link_status(),
+        )?;
+        if self.phy_id() != 0 {
+            self.config_fc_after_link_up(duplex)?;
+        }
+        info!(
+            "igb: sgmii link up, {} Mb/s {:?} duplex, flow control {:?}",
+            speed.mbps(),
+            duplex,
+            self.flow_control()
+        );
+        Ok((speed, duplex))
+    }
+}
@@ -0,0 +1,642 @@
+//! PHY drivers, selected from the identifier in MII registers 2 and 3.
+
+use core::time::Duration;
+
+use tock_registers::interfaces::Readable;
+
+use crate::cable::{i82580_cable_length, m88_cable_length, vct_diagnostics, CableDiagnostics};
+use crate::hal::IgbHal;
+use crate::igb::Igb;
+use crate::mac::IgbPhyType;
+use crate::mdio::{
+    PHY_1000T_CTRL, PHY_1000T_STATUS, PHY_AUTONEG_ADV, PHY_CONTROL, PHY_LP_ABILITY, PHY_STATUS,
+};
+use crate::regs::STATUS;
+use crate::{IgbError, IgbResult};
+
+/// Software reset, self-clearing.
+pub const MII_CR_RESET: u16 = 1 << 15;
+/// Loopback of the transmitted data to the receive path.
+pub const MII_CR_LOOPBACK: u16 = 1 << 14;
+/// Speed selection, least significant bit.
+pub const MII_CR_SPEED_SELECT_LSB: u16 = 1 << 13;
+/// Autonegotiation enable.
+pub const MII_CR_AUTO_NEG_EN: u16 = 1 << 12;
+/// Power down.
+pub const MII_CR_POWER_DOWN: u16 = 1 << 11;
+/// Restart autonegotiation, self-clearing.
+pub const MII_CR_RESTART_AUTO_NEG: u16 = 1 << 9;
+/// Full duplex.
+pub const MII_CR_FULL_DUPLEX: u16 = 1 << 8;
+/// Speed selection, most significant bit.
+pub const MII_CR_SPEED_SELECT_MSB: u16 = 1 << 6;
+
+/// Link status, latched low.
+pub const MII_SR_LINK_STATUS: u16 = 1 << 2;
+/// Autonegotiation complete.
+pub const MII_SR_AUTONEG_COMPLETE: u16 = 1 << 5;
+
+/// 10BASE-T half duplex ability, in the advertisement and link partner registers.
+pub const NWAY_AR_10T_HD: u16 = 1 << 5;
+/// 10BASE-T full duplex ability.
+pub const NWAY_AR_10T_FD: u16 = 1 << 6;
+/// 100BASE-TX half duplex ability.
+pub const NWAY_AR_100TX_HD: u16 = 1 << 7;
+/// 100BASE-TX full duplex ability.
+pub const NWAY_AR_100TX_FD: u16 = 1 << 8;
+/// Symmetric pause ability.
+pub const NWAY_AR_PAUSE: u16 = 1 << 10;
+/// Asymmetric pause direction ability.
+pub const NWAY_AR_ASM_DIR: u16 = 1 << 11;
+/// 1000BASE-T half duplex advertisement.
+pub const CR_1000T_HD_CAPS: u16 = 1 << 8;
+/// 1000BASE-T full duplex advertisement.
+pub const CR_1000T_FD_CAPS: u16 = 1 << 9;
+/// Link partner 1000BASE-T half duplex ability.
+pub const SR_1000T_LP_HD_CAPS: u16 = 1 << 10;
+/// Link partner 1000BASE-T full duplex ability.
+pub const SR_1000T_LP_FD_CAPS: u16 = 1 << 11;
+
+/// Marvell PHY Specific Status, also implemented by the I210 internal PHY.
+const M88_PHY_SPEC_STATUS: u32 = 0x11;
+/// Full duplex.
+const M88_PSSR_DPLX: u16 = 1 << 13;
+/// Speed and duplex resolved.
+const M88_PSSR_SPD_DPLX_RESOLVED: u16 = 1 << 11;
+/// Speed, two bits.
+const M88_PSSR_SPEED_SHIFT: u16 = 14;
+
+/// Marvell PHY Specific Control, also implemented by the I210 internal PHY.
+const M88_PHY_SPEC_CTRL: u32 = 0x10;
+/// MDI crossover mode, two bits: 0b00 MDI, 0b01 MDI-X, 0b11 automatic.
+const M88_PSCR_MDI_MASK: u16 = 0b11 << 5;
+/// Manual MDI-X.
+const M88_PSCR_MDIX_MANUAL: u16 = 0b01 << 5;
+/// Automatic crossover.
+const M88_PSCR_AUTO_X: u16 = 0b11 << 5;
+/// Downshift enable, on the gen2 Alaska PHYs and the I210.
+const M88_PSCR_DOWNSHIFT_ENABLE: u16 = 1 << 11;
+/// Number of gigabit attempts before downshifting minus one, three bits.
+const M88_PSCR_DOWNSHIFT_SHIFT: u16 = 12;
+/// Marvell Extended PHY Specific Control of the M88E1111.
+const M88_EXT_PHY_SPEC_CTRL: u32 = 0x14;
+/// Downshift enable, on the M88E1111.
+const M88_EPSCR_DOWNSHIFT_ENABLE: u16 = 1 << 8;
+/// Number of gigabit attempts before downshifting minus one, three bits.
+const M88_EPSCR_DOWNSHIFT_SHIFT: u16 = 9;
+/// Resolved MDI-X, in the PHY Specific Status.
+const M88_PSSR_MDIX: u16 = 1 << 6;
+
+/// IGP Port Status.
+const IGP_PHY_PORT_STATUS: u32 = 0x11;
+/// Speed, 0b11 for 1000 Mb/s and 0b10 for 100 Mb/s.
+const IGP_PSSR_SPEED_SHIFT: u16 = 14;
+/// Full duplex.
+const IGP_PSSR_FULL_DUPLEX: u16 = 1 << 9;
+/// Resolved MDI-X.
+const IGP_PSSR_MDIX: u16 = 1 << 11;
+/// IGP Port Control.
+const IGP_PHY_PORT_CTRL: u32 = 0x12;
+/// Automatic crossover.
+const IGP_PSCR_AUTO_MDIX: u16 = 1 << 12;
+/// MDI-X when crossover is not automatic, MDI otherwise.
+const IGP_PSCR_FORCE_MDIX: u16 = 1 << 13;
+
+/// 82580 PHY Control 2.
+const I82580_PHY_CTRL_2: u32 = 0x12;
+/// MDI-X when crossover is not automatic, MDI otherwise.
+const I82580_PHY_CTRL2_MANUAL_MDIX: u16 = 1 << 9;
+/// Automatic crossover.
+const I82580_PHY_CTRL2_AUTO_MDIX: u16 = 1 << 10;
+/// 82580 PHY Status 2.
+const I82580_PHY_STATUS_2: u32 = 26;
+/// Resolved MDI-X, in PHY Status 2.
+const I82580_PHY_STATUS2_MDIX: u16 = 1 << 11;
+/// 82580 PHY Configuration.
+const I82580_CFG_REG: u32 = 22;
+/// Gigabit to 100 Mb/s downshift.
+const I82580_CFG_ENABLE_DOWNSHIFT: u16 = 3 << 10;
+/// 82580 PHY Control.
+const I82580_CTRL_REG: u32 = 23;
+/// Number of gigabit attempts before downshifting minus one, three bits.
+const I82580_CTRL_DOWNSHIFT_SHIFT: u16 = 10;
+/// Highest number of gigabit attempts before downshifting.
+pub(crate) const MAX_DOWNSHIFT_RETRIES: u8 = 8;
+
+/// Deadline for the software reset bit to self-clear.
+const PHY_RESET_TIMEOUT: Duration = Duration::from_millis(100);
+/// Deadline for the PHY to load its configuration from the NVM after a reset.
+const PHY_CFG_DONE_TIMEOUT: Duration = Duration::from_millis(100);
+/// EEMNGCTL.CFG_DONE of port 0, the following ports use the next bits.
+const EEMNGCTL_CFG_DONE_PORT_0: u32 = 1 << 18;
+
+/// Identifier bits that do not hold the revision.
+const PHY_REVISION_MASK: u32 = 0xFFFF_FFF0;
+const M88E1111_I_PHY_ID: u32 = 0x0141_0CC0;
+const M88E1112_E_PHY_ID: u32 = 0x0141_0C90;
+const I347AT4_E_PHY_ID: u32 = 0x0141_0DC0;
+const M88E1512_E_PHY_ID: u32 = 0x0141_0DD0;
+const M88E1340M_E_PHY_ID: u32 = 0x0141_0DF0;
+const M88E1543_E_PHY_ID: u32 = 0x0141_0EA0;
+const IGP03E1000_E_PHY_ID: u32 = 0x02A8_0390;
+const I82580_I_PHY_ID: u32 = 0x0154_03A0;
+const I350_I_PHY_ID: u32 = 0x0154_03B0;
+const I210_I_PHY_ID: u32 = 0x0141_0C00;
+
+/// Speed of an established link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkSpeed {
+    /// 10 Mb/s.
+    Speed10,
+    /// 100 Mb/s.
+    Speed100,
+    /// 1000 Mb/s.
+    Speed1000,
+}
+
+impl LinkSpeed {
+    /// Returns the speed in Mb/s.
+    pub fn mbps(self) -> u16 {
+        match self {
+            Self::Speed10 => 10,
+            Self::Speed100 => 100,
+            Self::Speed1000 => 1000,
+        }
+    }
+}
+
+/// Crossover of the transmit and receive pairs, requested or resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MdiMode {
+    /// Crossover resolved by the PHY, only valid as a request.
+    Auto,
+    /// Straight pairs.
+    Mdi,
+    /// Crossed pairs.
+    MdiX,
+}
+
+/// Duplex mode of an established link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Duplex {
+    /// Half duplex.
+    Half,
+    /// Full duplex.
+    Full,
+}
+
+impl IgbPhyType {
+    /// Returns the PHY type with identifier `id`, or `None` if no driver handles it.
+    pub fn from_phy_id(id: u32) -> Option<Self> {
+        let phy_type = match id & PHY_REVISION_MASK {
+            M88E1111_I_PHY_ID | M88E1112_E_PHY_ID | I347AT4_E_PHY_ID | M88E1512_E_PHY_ID
+            | M88E1340M_E_PHY_ID | M88E1543_E_PHY_ID => Self::M88,
+            IGP03E1000_E_PHY_ID => Self::Igp,
+            I82580_I_PHY_ID | I350_I_PHY_ID => Self::Internal82580,
+            I210_I_PHY_ID => Self::InternalI210,
+            _ => return None,
+        };
+        Some(phy_type)
+    }
+}
+
+/// Operations that differ between the PHYs paired with igb controllers.
+pub trait Phy<H: IgbHal>: Sync {
+    /// Returns the type of this PHY.
+    fn phy_type(&self) -> IgbPhyType;
+
+    /// Resets the PHY and waits for it to be usable again.
+    fn reset(&self, igb: &Igb<H>) -> IgbResult {
+        igb.phy_reset()
+    }
+
+    /// Enables and restarts autonegotiation with the advertised abilities.
+    fn restart_autoneg(&self, igb: &Igb<H>) -> IgbResult {
+        let control = igb.read_mdi(PHY_CONTROL)?;
+        igb.write_mdi(
+            PHY_CONTROL,
+            control | MII_CR_AUTO_NEG_EN | MII_CR_RESTART_AUTO_NEG,
+        )
+    }
+
+    /// Disables autonegotiation and forces `speed` and `duplex`.
+    fn force_speed_duplex(&self, igb: &Igb<H>, speed: LinkSpeed, duplex: Duplex) -> IgbResult {
+        let control = igb.read_mdi(PHY_CONTROL)?;
+        igb.write_mdi(PHY_CONTROL, forced_control(control, speed, duplex))
+    }
+
+    /// Returns the speed and duplex the PHY has resolved, or `None` while the link is down.
+    fn speed_duplex(&self, igb: &Igb<H>) -> IgbResult<Option<(LinkSpeed, Duplex)>>;
+
+    /// Selects the crossover mode, which takes effect on the next autonegotiation.
+    fn set_mdi_mode(&self, igb: &Igb<H>, _mode: MdiMode) -> IgbResult {
+        Err(IgbError::UnsupportedDevice(igb.device_id()))
+    }
+
+    /// Returns the crossover resolved by the PHY.
+    fn mdi_status(&self, igb: &Igb<H>) -> IgbResult<MdiMode> {
+        Err(IgbError::UnsupportedDevice(igb.device_id()))
+    }
+
+    /// Enables gigabit to 100 Mb/s downshift after `retries` failed gigabit attempts, or disables
+    /// it if `None`. `retries` is between 1 and 8.
+    fn set_downshift(&self, igb: &Igb<H>, _retries: Option<u8>) -> IgbResult {
+        Err(IgbError::UnsupportedDevice(igb.device_id()))
+    }
+
+    /// Tests the attached cable.
+    fn cable_diagnostics(&self, igb: &Igb<H>) -> IgbResult<CableDiagnostics> {
+        Err(IgbError::UnsupportedDevice(igb.device_id()))
+    }
+
+    /// Powers the PHY up or down.
+    fn set_power(&self, igb: &Igb<H>, on: bool) -> IgbResult {
+        let control = igb.read_mdi(PHY_CONTROL)?;
+        let control = if on {
+            control & !MII_CR_POWER_DOWN
+        } else {
+            control | MII_CR_POWER_DOWN
+        };
+        igb.write_mdi(PHY_CONTROL, control)
+    }
+}
+
+/// Returns MII control `control` with autonegotiation disabled and `speed` and `duplex` selected.
+fn forced_control(control: u16, speed: LinkSpeed, duplex: Duplex) -> u16 {
+    let mut control = control
+        & !(MII_CR_AUTO_NEG_EN
+            | MII_CR_RESTART_AUTO_NEG
+            | MII_CR_SPEED_SELECT_LSB
+            | MII_CR_SPEED_SELECT_MSB
+            | MII_CR_FULL_DUPLEX);
+    control |= match speed {
+        LinkSpeed::Speed10 => 0,
+        LinkSpeed::Speed100 => MII_CR_SPEED_SELECT_LSB,
+        LinkSpeed::Speed1000 => MII_CR_SPEED_SELECT_MSB,
+    };
+    if duplex == Duplex::Full {
+        control |= MII_CR_FULL_DUPLEX;
+    }
+    control
+}
+
+/// Returns the speed and duplex forced through MII control, or `None` if autonegotiation is
+/// enabled.
+fn forced_speed_duplex<H: IgbHal>(igb: &Igb<H>) -> IgbResult<Option<(LinkSpeed, Duplex)>> {
+    let control = igb.read_mdi(PHY_CONTROL)?;
+    if control & MII_CR_AUTO_NEG_EN != 0 {
+        return Ok(None);
+    }
+    let speed = match (
+        control & MII_CR_SPEED_SELECT_MSB != 0,
+        control & MII_CR_SPEED_SELECT_LSB != 0,
+    ) {
+        (true, _) => LinkSpeed::Speed1000,
+        (false, true) => LinkSpeed::Speed100,
+        (false, false) => LinkSpeed::Speed10,
+    };
+    let duplex = if control & MII_CR_FULL_DUPLEX != 0 {
+        Duplex::Full
+    } else {
+        Duplex::Half
+    };
+    Ok(Some((speed, duplex)))
+}
+
+/// Whether the link is up, reading MII status twice as the bit latches low.
+fn link_up<H: IgbHal>(igb: &Igb<H>) -> IgbResult<bool> {
+    igb.read_mdi(PHY_STATUS)?;
+    Ok(igb.read_mdi(PHY_STATUS)? & MII_SR_LINK_STATUS != 0)
+}
+
+/// Resolves the highest common denominator of the local and link partner abilities, as
+/// autonegotiation does.
+fn resolve_autoneg<H: IgbHal>(igb: &Igb<H>) -> IgbResult<Option<(LinkSpeed, Duplex)>> {
+    if let Some(forced) = forced_speed_duplex(igb)? {
+        return Ok(Some(forced));
+    }
+    if igb.read_mdi(PHY_STATUS)? & MII_SR_AUTONEG_COMPLETE == 0 {
+        return Ok(None);
+    }
+
+    let ctrl_1000 = igb.read_mdi(PHY_1000T_CTRL)?;
+    let status_1000 = igb.read_mdi(PHY_1000T_STATUS)?;
+    if ctrl_1000 & CR_1000T_FD_CAPS != 0 && status_1000 & SR_1000T_LP_FD_CAPS != 0 {
+        return Ok(Some((LinkSpeed::Speed1000, Duplex::Full)));
+    }
+    if ctrl_1000 & CR_1000T_HD_CAPS != 0 && status_1000 & SR_1000T_LP_HD_CAPS != 0 {
+        return Ok(Some((LinkSpeed::Speed1000, Duplex::Half)));
+    }
+
+    let common = igb.read_mdi(PHY_AUTONEG_ADV)? & igb.read_mdi(PHY_LP_ABILITY)?;
+    let resolved = if common & NWAY_AR_100TX_FD != 0 {
+        (LinkSpeed::Speed100, Duplex::Full)
+    } else if common & NWAY_AR_100TX_HD != 0 {
+        (LinkSpeed::Speed100, Duplex::Half)
+    } else if common & NWAY_AR_10T_FD != 0 {
+        (LinkSpeed::Speed10, Duplex::Full)
+    } else if common & NWAY_AR_10T_HD != 0 {
+        (LinkSpeed::Speed10, Duplex::Half)
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(resolved))
+}
+
+/// Decodes the Marvell PHY Specific Status register.
+fn m88_speed_duplex<H: IgbHal>(igb: &Igb<H>) -> IgbResult<Option<(LinkSpeed, Duplex)>> {
+    if !link_up(igb)? {
+        return Ok(None);
+    }
+    let status = igb.read_mdi(M88_PHY_SPEC_STATUS)?;
+    if status & M88_PSSR_SPD_DPLX_RESOLVED == 0 {
+        return Ok(None);
+    }
+    let speed = match status >> M88_PSSR_SPEED_SHIFT {
+        0b10 => LinkSpeed::Speed1000,
+        0b01 => LinkSpeed::Speed100,
+        _ => LinkSpeed::Speed10,
+    };
+    let duplex = if status & M88_PSSR_DPLX != 0 {
+        Duplex::Full
+    } else {
+        Duplex::Half
+    };
+    Ok(Some((speed, duplex)))
+}
+
+/// Writes MII control `control` together with a software reset, which Marvell PHYs need to apply
+/// speed, duplex and copper specific settings, and waits for the reset to complete.
+fn m88_commit<H: IgbHal>(igb: &Igb<H>, control: u16) -> IgbResult {
+    igb.write_mdi(PHY_CONTROL, control | MII_CR_RESET)?;
+    Igb::<H>::poll("phy software reset", PHY_RESET_TIMEOUT, || {
+        match igb.read_mdi(PHY_CONTROL) {
+            Ok(control) if control & MII_CR_RESET != 0 => None,
+            result => Some(result),
+        }
+    })??;
+    Ok(())
+}
+
+/// Selects the crossover mode in the Marvell PHY Specific Control.
+fn m88_set_mdi_mode<H: IgbHal>(igb: &Igb<H>, mode: MdiMode) -> IgbResult {
+    let bits = match mode {
+        MdiMode::Auto => M88_PSCR_AUTO_X,
+        MdiMode::Mdi => 0,
+        MdiMode::MdiX => M88_PSCR_MDIX_MANUAL,
+    };
+    let pscr = igb.read_mdi(M88_PHY_SPEC_CTRL)? & !M88_PSCR_MDI_MASK;
+    igb.write_mdi(M88_PHY_SPEC_CTRL, pscr | bits)?;
+    m88_commit(igb, igb.read_mdi(PHY_CONTROL)?)
+}
+
+/// Decodes the crossover resolved in the Marvell PHY Specific Status.
+fn m88_mdi_status<H: IgbHal>(igb: &Igb<H>) -> IgbResult<MdiMode> {
+    Ok(if igb.read_mdi(M88_PHY_SPEC_STATUS)? & M88_PSSR_MDIX != 0 {
+        MdiMode::MdiX
+    } else {
+        MdiMode::Mdi
+    })
+}
+
+/// Programs the downshift enable bit at `enable` and the retry count at `shift` of register
+/// `reg`, then commits it.
+fn m88_set_downshift<H: IgbHal>(
+    igb: &Igb<H>,
+    reg: u32,
+    enable: u16,
+    shift: u16,
+    retries: Option<u8>,
+) -> IgbResult {
+    let mut value = igb.read_mdi(reg)? & !(enable | 0b111 << shift);
+    if let Some(retries) = retries {
+        value |= enable | (retries as u16 - 1) << shift;
+    }
+    igb.write_mdi(reg, value)?;
+    m88_commit(igb, igb.read_mdi(PHY_CONTROL)?)
+}
+
+/// Waits for the PHY of this port to load its configuration from the NVM.
+fn wait_cfg_done<H: IgbHal>(igb: &Igb<H>) {
+    let mask = EEMNGCTL_CFG_DONE_PORT_0 << igb.regs().status.read(STATUS::LAN_ID);
+    let done = Igb::<H>::poll("phy configuration", PHY_CFG_DONE_TIMEOUT, || {
+        (igb.regs().eemngctl.get() & mask != 0).then_some(())
+    });
+    if done.is_err() {
+        // the configuration cycle does not run on boards without management firmware
+        debug!("phy configuration cycle not completed");
+    }
+}
+
+/// External Marvell PHY.
+pub struct M88Phy;
+
+impl<H: IgbHal> Phy<H> for M88Phy {
+    fn phy_type(&self) -> IgbPhyType {
+        IgbPhyType::M88
+    }
+
+    fn reset(&self, igb: &Igb<H>) -> IgbResult {
+        igb.phy_reset()?;
+        if igb.phy_reset_blocked() {
+            // the software reset is blocked as well
+            return Ok(());
+        }
+        // the copper specific settings only take effect after a software reset
+        let control = igb.read_mdi(PHY_CONTROL)?;
+        m88_commit(igb, control)
+    }
+
+    fn force_speed_duplex(&self, igb: &Igb<H>, speed: LinkSpeed, duplex: Duplex) -> IgbResult {
+        let control = igb.read_mdi(PHY_CONTROL)?;
+        m88_commit(igb, forced_control(control, speed, duplex))
+    }
+
+    fn speed_duplex(&self, igb: &Igb<H>) -> IgbResult<Option<(LinkSpeed, Duplex)>> {
+        m88_speed_duplex(igb)
+    }
+
+    fn set_mdi_mode(&self, igb: &Igb<H>, mode: MdiMode) -> IgbResult {
+        m88_set_mdi_mode(igb, mode)
+    }
+
+    fn mdi_status(&self, igb: &Igb<H>) -> IgbResult<MdiMode> {
+        m88_mdi_status(igb)
+    }
+
+    fn set_downshift(&self, igb: &Igb<H>, retries: Option<u8>) -> IgbResult {
+        match igb.phy_id() & PHY_REVISION_MASK {
+            M88E1111_I_PHY_ID => m88_set_downshift(
+                igb,
+                M88_EXT_PHY_SPEC_CTRL,
+                M88_EPSCR_DOWNSHIFT_ENABLE,
+                M88_EPSCR_DOWNSHIFT_SHIFT,
+                retries,
+            ),
+            _ => m88_set_downshift(
+                igb,
+                M88_PHY_SPEC_CTRL,
+                M88_PSCR_DOWNSHIFT_ENABLE,
+                M88_PSCR_DOWNSHIFT_SHIFT,
+                retries,
+            ),
+        }
+    }
+
+    fn cable_diagnostics(&self, igb: &Igb<H>) -> IgbResult<CableDiagnostics> {
+        match igb.phy_id() & PHY_REVISION_MASK {
+            M88E1111_I_PHY_ID => m88_cable_length(igb),
+            _ => vct_diagnostics(igb),
+        }
+    }
+}
+
+/// Intel IGP PHY integrated in the 82575 and 82576.
+pub struct IgpPhy;
+
+impl<H: IgbHal> Phy<H> for IgpPhy {
+    fn phy_type(&self) -> IgbPhyType {
+        IgbPhyType::Igp
+    }
+
+    fn speed_duplex(&self, igb: &Igb<H>) -> IgbResult<Option<(LinkSpeed, Duplex)>> {
+        if !link_up(igb)? {
+            return Ok(None);
+        }
+        let status = igb.read_mdi(IGP_PHY_PORT_STATUS)?;
+        let speed = match status >> IGP_PSSR_SPEED_SHIFT {
+            0b11 => LinkSpeed::Speed1000,
+            0b10 => LinkSpeed::Speed100,
+            _ => LinkSpeed::Speed10,
+        };
+        let duplex = if status & IGP_PSSR_FULL_DUPLEX != 0 {
+            Duplex::Full
+        } else {
+            Duplex::Half
+        };
+        Ok(Some((speed, duplex)))
+    }
+
+    fn set_mdi_mode(&self, igb: &Igb<H>, mode: MdiMode) -> IgbResult {
+        let bits = match mode {
+            MdiMode::Auto => IGP_PSCR_AUTO_MDIX,
+            MdiMode::Mdi => 0,
+            MdiMode::MdiX => IGP_PSCR_FORCE_MDIX,
+        };
+        let mask = IGP_PSCR_AUTO_MDIX | IGP_PSCR_FORCE_MDIX;
+        let ctrl = igb.read_mdi(IGP_PHY_PORT_CTRL)? & !mask;
+        igb.write_mdi(IGP_PHY_PORT_CTRL, ctrl | bits)
+    }
+
+    fn mdi_status(&self, igb: &Igb<H>) -> IgbResult<MdiMode> {
+        Ok(if igb.read_mdi(IGP_PHY_PORT_STATUS)? & IGP_PSSR_MDIX != 0 {
+            MdiMode::MdiX
+        } else {
+            MdiMode::Mdi
+        })
+    }
+}
+
+/// Internal PHY of the 82580 and I350.
+pub struct I82580Phy;
+
+impl<H: IgbHal> Phy<H> for I82580Phy {
+    fn phy_type(&self) -> IgbPhyType {
+        IgbPhyType::Internal82580
+    }
+
+    fn reset(&self, igb: &Igb<H>) -> IgbResult {
+        igb.phy_reset()?;
+        wait_cfg_done(igb);
+        Ok(())
+    }
+
+    fn speed_duplex(&self, igb: &Igb<H>) -> IgbResult<Option<(LinkSpeed, Duplex)>> {
+        if !link_up(igb)? {
+            return Ok(None);
+        }
+        resolve_autoneg(igb)
+    }
+
+    fn set_mdi_mode(&self, igb: &Igb<H>, mode: MdiMode) -> IgbResult {
+        let bits = match mode {
+            MdiMode::Auto => I82580_PHY_CTRL2_AUTO_MDIX,
+            MdiMode::Mdi => 0,
+            MdiMode::MdiX => I82580_PHY_CTRL2_MANUAL_MDIX,
+        };
+        let mask = I82580_PHY_CTRL2_AUTO_MDIX | I82580_PHY_CTRL2_MANUAL_MDIX;
+        let ctrl = igb.read_mdi(I82580_PHY_CTRL_2)? & !mask;
+        igb.write_mdi(I82580_PHY_CTRL_2, ctrl | bits)
+    }
+
+    fn mdi_status(&self, igb: &Igb<H>) -> IgbResult<MdiMode> {
+        Ok(
+            if igb.read_mdi(I82580_PHY_STATUS_2)? & I82580_PHY_STATUS2_MDIX != 0 {
+                MdiMode::MdiX
+            } else {
+                MdiMode::Mdi
+            },
+        )
+    }
+
+    fn set_downshift(&self, igb: &Igb<H>, retries: Option<u8>) -> IgbResult {
+        let mut cfg = igb.read_mdi(I82580_CFG_REG)? & !I82580_CFG_ENABLE_DOWNSHIFT;
+        let mut ctrl = igb.read_mdi(I82580_CTRL_REG)? & !(0b111 << I82580_CTRL_DOWNSHIFT_SHIFT);
+        if let Some(retries) = retries {
+            cfg |= I82580_CFG_ENABLE_DOWNSHIFT;
+            ctrl |= (retries as u16 - 1) << I82580_CTRL_DOWNSHIFT_SHIFT;
+        }
+        igb.write_mdi(I82580_CTRL_REG, ctrl)?;
+        igb.write_mdi(I82580_CFG_REG, cfg)
+    }
+
+    fn cable_diagnostics(&self, igb: &Igb<H>) -> IgbResult<CableDiagnostics> {
+        i82580_cable_length(igb)
+    }
+}
+
+/// Internal PHY of the I210 and I211.
+pub struct I210Phy;
+
+impl<H: IgbHal> Phy<H> for I210Phy {
+    fn phy_type(&self) -> IgbPhyType {
+        IgbPhyType::InternalI210
+    }
+
+    fn reset(&self, igb: &Igb<H>) -> IgbResult {
+        igb.phy_reset()?;
+        wait_cfg_done(igb);
+        Ok(())
+    }
+
+    fn force_speed_duplex(&self, igb: &Igb<H>, speed: LinkSpeed, duplex: Duplex) -> IgbResult {
+        let control = igb.read_mdi(PHY_CONTROL)?;
+        m88_commit(igb, forced_control(control, speed, duplex))
+    }
+
+    fn speed_duplex(&self, igb: &Igb<H>) -> IgbResult<Option<(LinkSpeed, Duplex)>> {
+        m88_speed_duplex(igb)
+    }
+
+    fn set_mdi_mode(&self, igb: &Igb<H>, mode: MdiMode) -> IgbResult {
+        m88_set_mdi_mode(igb, mode)
+    }
+
+    fn mdi_status(&self, igb: &Igb<H>) -> IgbResult<MdiMode> {
+        m88_mdi_status(igb)
+    }
+
+    fn set_downshift(&self, igb: &Igb<H>, retries: Option<u8>) -> IgbResult {
+        m88_set_downshift(
+            igb,
+            M88_PHY_SPEC_CTRL,
+            M88_PSCR_DOWNSHIFT_ENABLE,
+            M88_PSCR_DOWNSHIFT_SHIFT,
+            retries,
+        )
+    }
+
+    fn cable_diagnostics(&self, igb: &Igb<H>) -> IgbResult<CableDiagnostics> {
+        vct_diagnostics(igb)
+    }
+}
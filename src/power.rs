@@ -0,0 +1,91 @@
+//! Power management of the PHY and of the SerDes link.
+
+use tock_registers::interfaces::{ReadWriteable, Readable};
+
+use crate::hal::IgbHal;
+use crate::igb::Igb;
+use crate::mac::IgbMacType;
+use crate::regs::{CTRL_EXT, FACTPS, FWSM, MANC, PCS_CFG, PHPM};
+use crate::{IgbError, IgbResult};
+
+impl<H: IgbHal> Igb<H> {
+    /// Whether the management controller shares the port with the host, receiving its traffic
+    /// through the link.
+    fn mng_pass_thru(&self) -> bool {
+        let manc = self.regs().manc.extract();
+        if !manc.is_set(MANC::RCV_TCO_EN) {
+            return false;
+        }
+        let fwsm = self.regs().fwsm.extract();
+        if fwsm.read(FWSM::MODE) != 0 {
+            // the firmware reports its mode, which only holds while its clock runs
+            fwsm.matches_all(FWSM::MODE::PassThrough) && !self.regs().factps.is_set(FACTPS::MNGCG)
+        } else {
+            manc.is_set(MANC::SMBUS_EN) && !manc.is_set(MANC::ASF_EN)
+        }
+    }
+
+    /// Whether the management controller blocks resets of the PHY.
+    pub(crate) fn phy_reset_blocked(&self) -> bool {
+        self.regs().manc.is_set(MANC::BLK_PHY_RST_ON_IDE)
+    }
+
+    /// Powers the copper PHY down through MII control, or shuts the PCS and the SFP optics down
+    /// on SerDes ports. The link goes down and stays down until [`Self::power_up_phy`], unless
+    /// the management controller uses the link, in which case the PHY stays powered.
+    pub fn power_down_phy(&self) -> IgbResult {
+        if self.mng_pass_thru() || self.phy_reset_blocked() {
+            info!("igb: phy kept powered for the management controller");
+            return Ok(());
+        }
+        if self.has_copper_phy() {
+            self.phy().set_power(self, false)?;
+        } else {
+            self.regs().pcs_cfg0.modify(PCS_CFG::PCS_EN::CLEAR);
+            // a high SDP3 turns the laser of the SFP module off
+            self.regs().ctrl_ext.modify(CTRL_EXT::SDP3_DATA::SET);
+        }
+        info!("igb: phy powered down");
+        Ok(())
+    }
+
+    /// Powers the PHY, or the PCS and the SFP optics, back up.
+    pub fn power_up_phy(&self) -> IgbResult {
+        if self.has_copper_phy() {
+            self.phy().set_power(self, true)?;
+        } else {
+            self.regs().ctrl_ext.modify(CTRL_EXT::SDP3_DATA::CLEAR);
+            self.regs().pcs_cfg0.modify(PCS_CFG::PCS_EN::SET);
+        }
+        info!("igb: phy powered up");
+        Ok(())
+    }
+
+    /// Fails unless the internal PHY has the PHPM power saving features.
+    fn check_phpm(&self) -> IgbResult {
+        if !matches!(
+            self.mac_type(),
+            IgbMacType::I350 | IgbMacType::I210 | IgbMacType::I211
+        ) {
+            return Err(IgbError::UnsupportedDevice(self.device_id()));
+        }
+        Ok(())
+    }
+
+    /// Enables or disables Smart Power Down, in which the PHY sleeps until it detects energy on
+    /// the cable. It applies from the next autonegotiation.
+    pub fn set_smart_power_down(&self, enable: bool) -> IgbResult {
+        self.check_phpm()?;
+        self.regs().phpm.modify(PHPM::SPD_EN.val(enable as u32));
+        debug!("smart power down {}", enable);
+        Ok(())
+    }
+
+    /// Enables or disables powering the PHY down while the link is down.
+    pub fn set_link_down_power_saving(&self, enable: bool) -> IgbResult {
+        self.check_phpm()?;
+        self.regs().phpm.modify(PHPM::GO_LINKD.val(enable as u32));
+        debug!("link down power saving {}", enable);
+        Ok(())
+    }
+}
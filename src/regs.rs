@@ -0,0 +1,911 @@
+//! Typed register map of the 82576 CSR space.
+//!
+//! Offsets and bit positions follow chapter 8 of the 82576 datasheet. Every register is accessed
+//! through the `tock-registers` interfaces, so bitfields are named and read-modify-write goes
+//! through [`ReadWriteable::modify`](tock_registers::interfaces::ReadWriteable::modify).
+
+use tock_registers::registers::{ReadOnly, ReadWrite, WriteOnly};
+use tock_registers::{register_bitfields, register_structs};
+
+/// Number of receive queues exposed by the register map.
+pub const NUM_RX_QUEUES: usize = 16;
+/// Number of transmit queues exposed by the register map.
+pub const NUM_TX_QUEUES: usize = 16;
+/// Offset of receive queue 0 in the legacy layout used by the 82575.
+pub const LEGACY_RX_QUEUE_BASE: usize = 0x2800;
+/// Offset of transmit queue 0 in the legacy layout used by the 82575.
+pub const LEGACY_TX_QUEUE_BASE: usize = 0x3800;
+/// Distance between two queues in the legacy layout.
+pub const LEGACY_QUEUE_STRIDE: usize = 0x100;
+/// Number of receive address (RAL/RAH) pairs.
+pub const NUM_RAR: usize = 16;
+/// Number of multicast table array entries.
+pub const NUM_MTA: usize = 128;
+/// Number of VLAN filter table array entries.
+pub const NUM_VFTA: usize = 128;
+/// Number of extended interrupt throttle registers.
+pub const NUM_EITR: usize = 25;
+/// Number of interrupt vector allocation registers.
+pub const NUM_IVAR: usize = 8;
+/// Number of iNVM data dwords of the I210 and I211.
+pub const NUM_INVM_DATA: usize = 64;
+
+register_bitfields! [
+    u32,
+
+    /// Device Control Register.
+    pub CTRL [
+        /// Full-Duplex.
+        FD OFFSET(0) NUMBITS(1) [],
+        /// GIO Master Disable.
+        GIO_MASTER_DISABLE OFFSET(2) NUMBITS(1) [],
+        /// Link Reset.
+        LRST OFFSET(3) NUMBITS(1) [],
+        /// Set Link Up.
+        SLU OFFSET(6) NUMBITS(1) [],
+        /// Invert Loss-of-Signal.
+        ILOS OFFSET(7) NUMBITS(1) [],
+        /// Speed selection, only used when FRCSPD is set.
+        SPEED OFFSET(8) NUMBITS(2) [
+            Speed10 = 0b00,
+            Speed100 = 0b01,
+            Speed1000 = 0b10
+        ],
+        /// Force Speed.
+        FRCSPD OFFSET(11) NUMBITS(1) [],
+        /// Force Duplex.
+        FRCDPLX OFFSET(12) NUMBITS(1) [],
+        /// SDP0 Data Value.
+        SDP0_DATA OFFSET(18) NUMBITS(1) [],
+        /// SDP1 Data Value.
+        SDP1_DATA OFFSET(19) NUMBITS(1) [],
+        /// D3Cold Wakeup Capability Advertisement Enable.
+        ADVD3WUC OFFSET(20) NUMBITS(1) [],
+        /// PHY Power-Management Enable.
+        EN_PHY_PWR_MGMT OFFSET(21) NUMBITS(1) [],
+        /// SDP0 Pin Direction.
+        SDP0_IODIR OFFSET(22) NUMBITS(1) [],
+        /// SDP1 Pin Direction.
+        SDP1_IODIR OFFSET(23) NUMBITS(1) [],
+        /// Port Software Reset.
+        RST OFFSET(26) NUMBITS(1) [],
+        /// Receive Flow Control Enable.
+        RFCE OFFSET(27) NUMBITS(1) [],
+        /// Transmit Flow Control Enable.
+        TFCE OFFSET(28) NUMBITS(1) [],
+        /// VLAN Mode Enable.
+        VME OFFSET(30) NUMBITS(1) [],
+        /// PHY Reset.
+        PHY_RST OFFSET(31) NUMBITS(1) []
+    ],
+
+    /// Device Status Register.
+    pub STATUS [
+        /// Full Duplex.
+        FD OFFSET(0) NUMBITS(1) [],
+        /// Link Up.
+        LU OFFSET(1) NUMBITS(1) [],
+        /// LAN ID.
+        LAN_ID OFFSET(2) NUMBITS(2) [],
+        /// Transmission Paused.
+        TXOFF OFFSET(4) NUMBITS(1) [],
+        /// Link speed setting.
+        SPEED OFFSET(6) NUMBITS(2) [
+            Speed10 = 0b00,
+            Speed100 = 0b01,
+            Speed1000 = 0b10,
+            Speed1000Alt = 0b11
+        ],
+        /// Auto-Speed Detection Value.
+        ASDV OFFSET(8) NUMBITS(2) [],
+        /// PHY Reset Asserted.
+        PHYRA OFFSET(10) NUMBITS(1) [],
+        /// GIO Master Enable Status.
+        GIO_MASTER_ENABLE OFFSET(19) NUMBITS(1) []
+    ],
+
+    /// EEPROM/Flash Control Register.
+    pub EEC [
+        /// Clock input to the EEPROM.
+        EE_SK OFFSET(0) NUMBITS(1) [],
+        /// Chip select input to the EEPROM.
+        EE_CS OFFSET(1) NUMBITS(1) [],
+        /// Data input to the EEPROM.
+        EE_DI OFFSET(2) NUMBITS(1) [],
+        /// Data output from the EEPROM.
+        EE_DO OFFSET(3) NUMBITS(1) [],
+        /// Flash Write Enable Control.
+        FWE OFFSET(4) NUMBITS(2) [],
+        /// Request EEPROM Access.
+        EE_REQ OFFSET(6) NUMBITS(1) [],
+        /// Grant EEPROM Access.
+        EE_GNT OFFSET(7) NUMBITS(1) [],
+        /// EEPROM Present.
+        EE_PRES OFFSET(8) NUMBITS(1) [],
+        /// EEPROM Auto Read Done.
+        AUTO_RD OFFSET(9) NUMBITS(1) [],
+        /// EEPROM Address Size.
+        EE_ADDR_SIZE OFFSET(10) NUMBITS(1) [],
+        /// EEPROM Size.
+        EE_SIZE OFFSET(11) NUMBITS(4) [],
+        /// Flash Detected.
+        FLASH_DETECTED OFFSET(19) NUMBITS(1) [],
+        /// Update the flash with the content of the shadow RAM.
+        FLUPD OFFSET(23) NUMBITS(1) [],
+        /// Flash Update Done.
+        FLUDONE OFFSET(26) NUMBITS(1) []
+    ],
+
+    /// EEPROM Read Register.
+    pub EERD [
+        /// Start Read.
+        START OFFSET(0) NUMBITS(1) [],
+        /// Read Done.
+        DONE OFFSET(1) NUMBITS(1) [],
+        /// Read Address (in words).
+        ADDR OFFSET(2) NUMBITS(14) [],
+        /// Read Data.
+        DATA OFFSET(16) NUMBITS(16) []
+    ],
+
+    /// EEPROM Write Register.
+    pub EEWR [
+        /// Start Write.
+        START OFFSET(0) NUMBITS(1) [],
+        /// Write Done.
+        DONE OFFSET(1) NUMBITS(1) [],
+        /// Write Address (in words).
+        ADDR OFFSET(2) NUMBITS(14) [],
+        /// Write Data.
+        DATA OFFSET(16) NUMBITS(16) []
+    ],
+
+    /// Extended Device Control Register.
+    pub CTRL_EXT [
+        /// SDP2 Data Value.
+        SDP2_DATA OFFSET(6) NUMBITS(1) [],
+        /// SDP3 Data Value.
+        SDP3_DATA OFFSET(7) NUMBITS(1) [],
+        /// SDP2 Pin Direction.
+        SDP2_IODIR OFFSET(10) NUMBITS(1) [],
+        /// SDP3 Pin Direction.
+        SDP3_IODIR OFFSET(11) NUMBITS(1) [],
+        /// EEPROM Reset.
+        EE_RST OFFSET(13) NUMBITS(1) [],
+        /// PF Reset Done.
+        PFRSTD OFFSET(14) NUMBITS(1) [],
+        /// Speed Select Bypass.
+        SPD_BYPS OFFSET(15) NUMBITS(1) [],
+        /// Relaxed Ordering Disabled.
+        RO_DIS OFFSET(17) NUMBITS(1) [],
+        /// SerDes Low Power Enable.
+        SDLPE OFFSET(18) NUMBITS(1) [],
+        /// PHY Power Down Enable.
+        PHYPDEN OFFSET(20) NUMBITS(1) [],
+        /// Link Mode.
+        LINK_MODE OFFSET(22) NUMBITS(2) [
+            DirectCopper = 0b00,
+            Kx = 0b01,
+            Sgmii = 0b10,
+            SerDes = 0b11
+        ],
+        /// I2C Enable.
+        I2C_ENA OFFSET(25) NUMBITS(1) [],
+        /// External VLAN Enable.
+        EXT_VLAN OFFSET(26) NUMBITS(1) [],
+        /// Driver Loaded.
+        DRV_LOAD OFFSET(28) NUMBITS(1) []
+    ],
+
+    /// MDI Control Register.
+    pub MDIC [
+        /// Data.
+        DATA OFFSET(0) NUMBITS(16) [],
+        /// PHY Register Address.
+        REGADD OFFSET(16) NUMBITS(5) [],
+        /// PHY Address.
+        PHYADD OFFSET(21) NUMBITS(5) [],
+        /// Opcode.
+        OP OFFSET(26) NUMBITS(2) [
+            Write = 0b01,
+            Read = 0b10
+        ],
+        /// Ready Bit.
+        READY OFFSET(28) NUMBITS(1) [],
+        /// Interrupt Enable.
+        I OFFSET(29) NUMBITS(1) [],
+        /// Error.
+        E OFFSET(30) NUMBITS(1) [],
+        /// Destination, set when the 82576 accesses an external PHY.
+        DEST OFFSET(31) NUMBITS(1) []
+    ],
+
+    /// MDC/MDIO Configuration Register of the 82580 and later.
+    pub MDICNFG [
+        /// PHY address used for external MDIO accesses.
+        PHY_ADDRESS OFFSET(21) NUMBITS(5) [],
+        /// MDIO shared by all ports.
+        COM_MDIO OFFSET(30) NUMBITS(1) [],
+        /// Destination, set for the external MDIO interface.
+        DESTINATION OFFSET(31) NUMBITS(1) []
+    ],
+
+    /// PHY Power Management of the 82580 and later internal PHYs.
+    pub PHPM [
+        /// Smart Power Down, the PHY sleeps until energy shows up on the cable.
+        SPD_EN OFFSET(0) NUMBITS(1) [],
+        /// Low Power Link Up in D0a.
+        D0_LPLU OFFSET(1) NUMBITS(1) [],
+        /// Low Power Link Up in the other power states.
+        D3_LPLU OFFSET(2) NUMBITS(1) [],
+        /// Go Link Disconnect, the PHY powers down while the link is down.
+        GO_LINKD OFFSET(5) NUMBITS(1) []
+    ],
+
+    /// Energy Efficient Ethernet Register of the I350 and later.
+    pub EEER [
+        /// Minimum time the link partner needs to wake from LPI, in microseconds.
+        TW_SYSTEM OFFSET(0) NUMBITS(16) [],
+        /// Enable entering LPI on transmit.
+        TX_LPI_EN OFFSET(16) NUMBITS(1) [],
+        /// Enable receiving LPI.
+        RX_LPI_EN OFFSET(17) NUMBITS(1) [],
+        /// Enable entering LPI while flow control pauses transmission.
+        LPI_FC OFFSET(18) NUMBITS(1) [],
+        /// EEE was negotiated with the link partner.
+        EEE_NEG OFFSET(29) NUMBITS(1) [],
+        /// Receive path is in LPI.
+        RX_LPI_STATUS OFFSET(30) NUMBITS(1) [],
+        /// Transmit path is in LPI.
+        TX_LPI_STATUS OFFSET(31) NUMBITS(1) []
+    ],
+
+    /// Internal PHY Configuration of the I350 and later.
+    pub IPCNFG [
+        /// Advertise EEE at 100 Mb/s.
+        EEE_100M_AN OFFSET(2) NUMBITS(1) [],
+        /// Advertise EEE at 1000 Mb/s.
+        EEE_1G_AN OFFSET(3) NUMBITS(1) []
+    ],
+
+    /// Interrupt cause bits shared by ICR, ICS, IMS, IMC and IAM.
+    pub INTERRUPT [
+        /// Transmit Descriptor Written Back.
+        TXDW OFFSET(0) NUMBITS(1) [],
+        /// Link Status Change.
+        LSC OFFSET(2) NUMBITS(1) [],
+        /// Receive Descriptor Minimum Threshold Reached.
+        RXDMT0 OFFSET(4) NUMBITS(1) [],
+        /// Missed Packet.
+        RX_MISS OFFSET(6) NUMBITS(1) [],
+        /// Receive Descriptor Written Back.
+        RXDW OFFSET(7) NUMBITS(1) [],
+        /// VM Mailbox.
+        VMMB OFFSET(8) NUMBITS(1) [],
+        /// General Purpose Interrupts on SDP0-3.
+        GPI_SDP OFFSET(11) NUMBITS(4) [],
+        /// Management Bus Interrupt.
+        MNG OFFSET(18) NUMBITS(1) [],
+        /// Time Sync Interrupt.
+        TIME_SYNC OFFSET(19) NUMBITS(1) [],
+        /// Fatal Error.
+        FER OFFSET(22) NUMBITS(1) [],
+        /// PCI Exception.
+        PCI_EXCEPTION OFFSET(24) NUMBITS(1) [],
+        /// SCE Error.
+        SCE OFFSET(25) NUMBITS(1) [],
+        /// Software Watchdog.
+        SW_WD OFFSET(26) NUMBITS(1) [],
+        /// Malicious Driver Detection.
+        MDDET OFFSET(28) NUMBITS(1) [],
+        /// TCP Timer Interrupt.
+        TCP_TIMER OFFSET(30) NUMBITS(1) [],
+        /// Interrupt Asserted.
+        INT_ASSERTED OFFSET(31) NUMBITS(1) []
+    ],
+
+    /// General Purpose Interrupt Enable.
+    pub GPIE [
+        /// Non Selective Interrupt clear on read.
+        NSICR OFFSET(0) NUMBITS(1) [],
+        /// Multiple MSI-X vectors.
+        MULTIPLE_MSIX OFFSET(4) NUMBITS(1) [],
+        /// Extended Interrupt Auto Mask Enable.
+        EIAME OFFSET(30) NUMBITS(1) [],
+        /// PBA Support.
+        PBA OFFSET(31) NUMBITS(1) []
+    ],
+
+    /// Receive Control Register.
+    pub RCTL [
+        /// Receiver Enable.
+        EN OFFSET(1) NUMBITS(1) [],
+        /// Store Bad Packets.
+        SBP OFFSET(2) NUMBITS(1) [],
+        /// Unicast Promiscuous Enabled.
+        UPE OFFSET(3) NUMBITS(1) [],
+        /// Multicast Promiscuous Enabled.
+        MPE OFFSET(4) NUMBITS(1) [],
+        /// Long Packet Reception Enable.
+        LPE OFFSET(5) NUMBITS(1) [],
+        /// Loopback mode.
+        LBM OFFSET(6) NUMBITS(2) [
+            Normal = 0b00,
+            Mac = 0b01
+        ],
+        /// Receive Descriptor Minimum Threshold Size.
+        RDMTS OFFSET(8) NUMBITS(2) [
+            Half = 0b00,
+            Quarter = 0b01,
+            Eighth = 0b10
+        ],
+        /// Multicast Offset.
+        MO OFFSET(12) NUMBITS(2) [],
+        /// Broadcast Accept Mode.
+        BAM OFFSET(15) NUMBITS(1) [],
+        /// Receive Buffer Size, used when SRRCTL.BSIZEPACKET is zero.
+        BSIZE OFFSET(16) NUMBITS(2) [
+            Size2048 = 0b00,
+            Size1024 = 0b01,
+            Size512 = 0b10,
+            Size256 = 0b11
+        ],
+        /// VLAN Filter Enable.
+        VFE OFFSET(18) NUMBITS(1) [],
+        /// Canonical Form Indicator Enable.
+        CFIEN OFFSET(19) NUMBITS(1) [],
+        /// Canonical Form Indicator bit value.
+        CFI OFFSET(20) NUMBITS(1) [],
+        /// Discard Pause Frames.
+        DPF OFFSET(22) NUMBITS(1) [],
+        /// Pass MAC Control Frames.
+        PMCF OFFSET(23) NUMBITS(1) [],
+        /// Strip Ethernet CRC from incoming packet.
+        SECRC OFFSET(26) NUMBITS(1) []
+    ],
+
+    /// Flow Control Transmit Timer Value.
+    pub FCTTV [
+        /// Transmit Timer Value, in 512 bit-time slots.
+        TTV OFFSET(0) NUMBITS(16) []
+    ],
+
+    /// Transmit Control Register.
+    pub TCTL [
+        /// Transmit Enable.
+        EN OFFSET(1) NUMBITS(1) [],
+        /// Pad Short Packets.
+        PSP OFFSET(3) NUMBITS(1) [],
+        /// Collision Threshold.
+        CT OFFSET(4) NUMBITS(8) [],
+        /// Collision Distance.
+        COLD OFFSET(12) NUMBITS(10) [],
+        /// Software XOFF Transmission.
+        SWXOFF OFFSET(22) NUMBITS(1) [],
+        /// Re-transmit on Late Collision.
+        RTLC OFFSET(24) NUMBITS(1) []
+    ],
+
+    /// Transmit IPG Register.
+    pub TIPG [
+        /// IPG Transmit Time.
+        IPGT OFFSET(0) NUMBITS(10) [],
+        /// IPG Receive Time 1.
+        IPGR1 OFFSET(10) NUMBITS(10) [],
+        /// IPG Receive Time 2.
+        IPGR2 OFFSET(20) NUMBITS(10) []
+    ],
+
+    /// I2C Command Register.
+    pub I2CCMD [
+        /// Data.
+        DATA OFFSET(0) NUMBITS(16) [],
+        /// I2C Register Address.
+        REGADD OFFSET(16) NUMBITS(8) [],
+        /// PHY Address.
+        PHYADD OFFSET(24) NUMBITS(3) [],
+        /// Opcode.
+        OP OFFSET(27) NUMBITS(1) [
+            Write = 0,
+            Read = 1
+        ],
+        /// Reset sequence.
+        RESET OFFSET(28) NUMBITS(1) [],
+        /// Ready Bit.
+        READY OFFSET(29) NUMBITS(1) [],
+        /// Interrupt Enable.
+        I OFFSET(30) NUMBITS(1) [],
+        /// Error.
+        E OFFSET(31) NUMBITS(1) []
+    ],
+
+    /// Flow Control Receive Threshold Low.
+    pub FCRTL [
+        /// Receive Threshold Low, in 16 byte units.
+        RTL OFFSET(4) NUMBITS(12) [],
+        /// XON Enable.
+        XONE OFFSET(31) NUMBITS(1) []
+    ],
+
+    /// Flow Control Receive Threshold High.
+    pub FCRTH [
+        /// Receive Threshold High, in 16 byte units.
+        RTH OFFSET(4) NUMBITS(12) []
+    ],
+
+    /// Receive Packet Buffer Size.
+    pub RXPBS [
+        /// Receive Packet Buffer Size in KB.
+        SIZE OFFSET(0) NUMBITS(7) []
+    ],
+
+    /// Transmit Packet Buffer Size.
+    pub TXPBS [
+        /// Transmit Packet Buffer Size in KB.
+        SIZE OFFSET(0) NUMBITS(6) []
+    ],
+
+    /// PCS Configuration 0 Register.
+    pub PCS_CFG [
+        /// PCS Enable.
+        PCS_EN OFFSET(3) NUMBITS(1) []
+    ],
+
+    /// PCS Link Control Register.
+    pub PCS_LCTL [
+        /// Forced Link Value.
+        FLV OFFSET(0) NUMBITS(1) [],
+        /// Forced Speed Value.
+        FSV OFFSET(1) NUMBITS(2) [
+            Speed10 = 0b00,
+            Speed100 = 0b01,
+            Speed1000 = 0b10
+        ],
+        /// Forced Duplex Value.
+        FDV OFFSET(3) NUMBITS(1) [],
+        /// Force Speed and Duplex.
+        FSD OFFSET(4) NUMBITS(1) [],
+        /// Force Link.
+        FORCE_LINK OFFSET(5) NUMBITS(1) [],
+        /// Force Flow Control.
+        FORCE_FCTRL OFFSET(7) NUMBITS(1) [],
+        /// Auto-Negotiation Enable.
+        AN_ENABLE OFFSET(16) NUMBITS(1) [],
+        /// Auto-Negotiation Restart.
+        AN_RESTART OFFSET(17) NUMBITS(1) [],
+        /// Auto-Negotiation Timeout Enable.
+        AN_TIMEOUT OFFSET(18) NUMBITS(1) [],
+        /// SGMII Auto-Negotiation Bypass.
+        AN_SGMII_BYPASS OFFSET(19) NUMBITS(1) [],
+        /// SGMII Auto-Negotiation Trigger.
+        AN_SGMII_TRIGGER OFFSET(20) NUMBITS(1) [],
+        /// Fast Link Timer.
+        FAST_LINK_TIMER OFFSET(24) NUMBITS(1) [],
+        /// Link OK Fix.
+        LINK_OK_FIX OFFSET(25) NUMBITS(1) []
+    ],
+
+    /// PCS Link Status Register.
+    pub PCS_LSTAT [
+        /// Link OK.
+        LINK_OK OFFSET(0) NUMBITS(1) [],
+        /// Speed.
+        SPEED OFFSET(1) NUMBITS(2) [
+            Speed10 = 0b00,
+            Speed100 = 0b01,
+            Speed1000 = 0b10
+        ],
+        /// Duplex Mode.
+        DUPLEX OFFSET(3) NUMBITS(1) [],
+        /// Sync OK.
+        SYNC_OK OFFSET(4) NUMBITS(1) [],
+        /// Auto-Negotiation Complete.
+        AN_COMPLETE OFFSET(16) NUMBITS(1) [],
+        /// Auto-Negotiation Page Received.
+        AN_PAGE_RECEIVED OFFSET(17) NUMBITS(1) [],
+        /// Auto-Negotiation Timed Out.
+        AN_TIMED_OUT OFFSET(18) NUMBITS(1) [],
+        /// Auto-Negotiation Remote Fault.
+        AN_REMOTE_FAULT OFFSET(19) NUMBITS(1) [],
+        /// Auto-Negotiation Error.
+        AN_ERROR_RWS OFFSET(20) NUMBITS(1) []
+    ],
+
+    /// 1000BASE-X base page layout shared by PCS_ANADV and PCS_LPAB.
+    pub PCS_AN [
+        /// Full-Duplex.
+        FD OFFSET(5) NUMBITS(1) [],
+        /// Half-Duplex.
+        HD OFFSET(6) NUMBITS(1) [],
+        /// Symmetric pause.
+        PAUSE OFFSET(7) NUMBITS(1) [],
+        /// Asymmetric pause direction.
+        ASM_DIR OFFSET(8) NUMBITS(1) [],
+        /// Remote Fault.
+        RFLT OFFSET(12) NUMBITS(2) [],
+        /// Acknowledge.
+        ACK OFFSET(14) NUMBITS(1) [],
+        /// Next Page.
+        NXTPG OFFSET(15) NUMBITS(1) []
+    ],
+
+    /// Receive Checksum Control.
+    pub RXCSUM [
+        /// IP Checksum Off-load Enable.
+        IPOFL OFFSET(8) NUMBITS(1) [],
+        /// TCP/UDP Checksum Off-load Enable.
+        TUOFL OFFSET(9) NUMBITS(1) [],
+        /// CRC Off-load Enable.
+        CRCOFL OFFSET(11) NUMBITS(1) [],
+        /// IP Payload Checksum Enable.
+        IPPCSE OFFSET(12) NUMBITS(1) [],
+        /// Packet Checksum Disable.
+        PCSD OFFSET(13) NUMBITS(1) []
+    ],
+
+    /// Receive Address High.
+    pub RAH [
+        /// Receive address high 16 bits.
+        RAH OFFSET(0) NUMBITS(16) [],
+        /// Address Select.
+        ASEL OFFSET(16) NUMBITS(2) [
+            Destination = 0b00,
+            Source = 0b01
+        ],
+        /// Pool Select.
+        POOLSEL OFFSET(18) NUMBITS(8) [],
+        /// Address Valid.
+        AV OFFSET(31) NUMBITS(1) []
+    ],
+
+    /// Management Control.
+    pub MANC [
+        /// SMBus enabled for the management controller.
+        SMBUS_EN OFFSET(0) NUMBITS(1) [],
+        /// ASF mode of the management controller.
+        ASF_EN OFFSET(1) NUMBITS(1) [],
+        /// Reception of TCO packets enabled.
+        RCV_TCO_EN OFFSET(17) NUMBITS(1) [],
+        /// PHY resets are blocked by the management controller.
+        BLK_PHY_RST_ON_IDE OFFSET(18) NUMBITS(1) []
+    ],
+
+    /// Function Active and Power State to Manageability.
+    pub FACTPS [
+        /// Manageability clock gated.
+        MNGCG OFFSET(29) NUMBITS(1) []
+    ],
+
+    /// Firmware Semaphore Register.
+    pub FWSM [
+        /// Manageability mode of the firmware.
+        MODE OFFSET(1) NUMBITS(3) [
+            None = 0,
+            Asf = 1,
+            PassThrough = 2
+        ]
+    ],
+
+    /// Software Semaphore Register.
+    pub SWSM [
+        /// Semaphore bit.
+        SMBI OFFSET(0) NUMBITS(1) [],
+        /// Software EEPROM Semaphore bit.
+        SWESMBI OFFSET(1) NUMBITS(1) [],
+        /// Wake MNG Clock.
+        WMNG OFFSET(2) NUMBITS(1) []
+    ],
+
+    /// Software-Firmware Synchronization.
+    pub SW_FW_SYNC [
+        /// Semaphores owned by software.
+        SW OFFSET(0) NUMBITS(16) [],
+        /// Semaphores owned by firmware.
+        FW OFFSET(16) NUMBITS(16) []
+    ],
+
+    /// iNVM Data, one dword of the integrated OTP memory.
+    pub INVM_DATA [
+        /// Type of the record starting at this dword.
+        RECORD_TYPE OFFSET(0) NUMBITS(3) [
+            Uninitialized = 0,
+            WordAutoload = 1,
+            CsrAutoload = 2,
+            PhyRegisterAutoload = 3,
+            RsaKeySha256 = 4,
+            Invalidated = 7
+        ],
+        /// NVM word address of a word autoload record.
+        WORD_ADDRESS OFFSET(9) NUMBITS(7) [],
+        /// NVM word data of a word autoload record.
+        WORD_DATA OFFSET(16) NUMBITS(16) []
+    ],
+
+    /// Split and Replication Receive Control.
+    pub SRRCTL [
+        /// Receive Buffer Size for Packet Buffer, in 1 KB units.
+        BSIZEPACKET OFFSET(0) NUMBITS(7) [],
+        /// Receive Buffer Size for Header Buffer, in 64 byte units.
+        BSIZEHEADER OFFSET(8) NUMBITS(4) [],
+        /// Receive Descriptor Minimum Threshold Size.
+        RDMTS OFFSET(20) NUMBITS(5) [],
+        /// Descriptor Type.
+        DESCTYPE OFFSET(25) NUMBITS(3) [
+            Legacy = 0b000,
+            AdvancedOneBuffer = 0b001,
+            AdvancedHeaderSplit = 0b010,
+            AdvancedHeaderReplicationAlways = 0b011,
+            AdvancedHeaderReplicationLargePacket = 0b100
+        ],
+        /// Drop Enabled.
+        DROP_EN OFFSET(31) NUMBITS(1) []
+    ],
+
+    /// Receive Descriptor Control.
+    pub RXDCTL [
+        /// Prefetch Threshold.
+        PTHRESH OFFSET(0) NUMBITS(5) [],
+        /// Host Threshold.
+        HTHRESH OFFSET(8) NUMBITS(5) [],
+        /// Write-Back Threshold.
+        WTHRESH OFFSET(16) NUMBITS(5) [],
+        /// Receive Queue Enable.
+        ENABLE OFFSET(25) NUMBITS(1) [],
+        /// Receive Software Flush.
+        SWFLUSH OFFSET(26) NUMBITS(1) []
+    ],
+
+    /// Transmit Descriptor Control.
+    pub TXDCTL [
+        /// Prefetch Threshold.
+        PTHRESH OFFSET(0) NUMBITS(5) [],
+        /// Host Threshold.
+        HTHRESH OFFSET(8) NUMBITS(5) [],
+        /// Write-Back Threshold.
+        WTHRESH OFFSET(16) NUMBITS(5) [],
+        /// Transmit Queue Enable.
+        ENABLE OFFSET(25) NUMBITS(1) [],
+        /// Transmit Software Flush.
+        SWFLSH OFFSET(26) NUMBITS(1) [],
+        /// Transmit Queue Priority.
+        PRIORITY OFFSET(27) NUMBITS(1) []
+    ]
+];
+
+register_structs! {
+    /// Registers of one receive queue, repeated every 0x40 bytes from 0xC000.
+    pub RxQueueRegs {
+        (0x00 => pub rdbal: ReadWrite<u32>),
+        (0x04 => pub rdbah: ReadWrite<u32>),
+        (0x08 => pub rdlen: ReadWrite<u32>),
+        (0x0C => pub srrctl: ReadWrite<u32, SRRCTL::Register>),
+        (0x10 => pub rdh: ReadWrite<u32>),
+        (0x14 => pub rxctl: ReadWrite<u32>),
+        (0x18 => pub rdt: ReadWrite<u32>),
+        (0x1C => _reserved0),
+        (0x28 => pub rxdctl: ReadWrite<u32, RXDCTL::Register>),
+        (0x2C => _reserved1),
+        (0x30 => pub rqdpc: ReadOnly<u32>),
+        (0x34 => _reserved2),
+        (0x40 => @END),
+    },
+
+    /// Registers of one transmit queue, repeated every 0x40 bytes from 0xE000.
+    pub TxQueueRegs {
+        (0x00 => pub tdbal: ReadWrite<u32>),
+        (0x04 => pub tdbah: ReadWrite<u32>),
+        (0x08 => pub tdlen: ReadWrite<u32>),
+        (0x0C => _reserved0),
+        (0x10 => pub tdh: ReadWrite<u32>),
+        (0x14 => pub txctl: ReadWrite<u32>),
+        (0x18 => pub tdt: ReadWrite<u32>),
+        (0x1C => _reserved1),
+        (0x28 => pub txdctl: ReadWrite<u32, TXDCTL::Register>),
+        (0x2C => _reserved2),
+        (0x38 => pub tdwbal: ReadWrite<u32>),
+        (0x3C => pub tdwbah: ReadWrite<u32>),
+        (0x40 => @END),
+    },
+
+    /// One receive address filter entry.
+    pub RarRegs {
+        (0x00 => pub ral: ReadWrite<u32>),
+        (0x04 => pub rah: ReadWrite<u32, RAH::Register>),
+        (0x08 => @END),
+    },
+
+    /// Statistics registers, all clear on read.
+    pub StatsRegs {
+        (0x00 => pub crcerrs: ReadOnly<u32>),
+        (0x04 => pub algnerrc: ReadOnly<u32>),
+        (0x08 => pub symerrs: ReadOnly<u32>),
+        (0x0C => pub rxerrc: ReadOnly<u32>),
+        (0x10 => pub mpc: ReadOnly<u32>),
+        (0x14 => pub scc: ReadOnly<u32>),
+        (0x18 => pub ecol: ReadOnly<u32>),
+        (0x1C => pub mcc: ReadOnly<u32>),
+        (0x20 => pub latecol: ReadOnly<u32>),
+        (0x24 => _reserved0),
+        (0x28 => pub colc: ReadOnly<u32>),
+        (0x2C => _reserved1),
+        (0x30 => pub dc: ReadOnly<u32>),
+        (0x34 => pub tncrs: ReadOnly<u32>),
+        (0x38 => pub sec: ReadOnly<u32>),
+        (0x3C => pub cexterr: ReadOnly<u32>),
+        (0x40 => pub rlec: ReadOnly<u32>),
+        (0x44 => _reserved2),
+        (0x48 => pub xonrxc: ReadOnly<u32>),
+        (0x4C => pub xontxc: ReadOnly<u32>),
+        (0x50 => pub xoffrxc: ReadOnly<u32>),
+        (0x54 => pub xofftxc: ReadOnly<u32>),
+        (0x58 => pub fcruc: ReadOnly<u32>),
+        (0x5C => pub prc64: ReadOnly<u32>),
+        (0x60 => pub prc127: ReadOnly<u32>),
+        (0x64 => pub prc255: ReadOnly<u32>),
+        (0x68 => pub prc511: ReadOnly<u32>),
+        (0x6C => pub prc1023: ReadOnly<u32>),
+        (0x70 => pub prc1522: ReadOnly<u32>),
+        (0x74 => pub gprc: ReadOnly<u32>),
+        (0x78 => pub bprc: ReadOnly<u32>),
+        (0x7C => pub mprc: ReadOnly<u32>),
+        (0x80 => pub gptc: ReadOnly<u32>),
+        (0x84 => _reserved3),
+        (0x88 => pub gorcl: ReadOnly<u32>),
+        (0x8C => pub gorch: ReadOnly<u32>),
+        (0x90 => pub gotcl: ReadOnly<u32>),
+        (0x94 => pub gotch: ReadOnly<u32>),
+        (0x98 => _reserved4),
+        (0xA0 => pub rnbc: ReadOnly<u32>),
+        (0xA4 => pub ruc: ReadOnly<u32>),
+        (0xA8 => pub rfc: ReadOnly<u32>),
+        (0xAC => pub roc: ReadOnly<u32>),
+        (0xB0 => pub rjc: ReadOnly<u32>),
+        (0xB4 => pub mgtprc: ReadOnly<u32>),
+        (0xB8 => pub mgtpdc: ReadOnly<u32>),
+        (0xBC => pub mgtptc: ReadOnly<u32>),
+        (0xC0 => pub torl: ReadOnly<u32>),
+        (0xC4 => pub torh: ReadOnly<u32>),
+        (0xC8 => pub totl: ReadOnly<u32>),
+        (0xCC => pub toth: ReadOnly<u32>),
+        (0xD0 => pub tpr: ReadOnly<u32>),
+        (0xD4 => pub tpt: ReadOnly<u32>),
+        (0xD8 => pub ptc64: ReadOnly<u32>),
+        (0xDC => pub ptc127: ReadOnly<u32>),
+        (0xE0 => pub ptc255: ReadOnly<u32>),
+        (0xE4 => pub ptc511: ReadOnly<u32>),
+        (0xE8 => pub ptc1023: ReadOnly<u32>),
+        (0xEC => pub ptc1522: ReadOnly<u32>),
+        (0xF0 => pub mptc: ReadOnly<u32>),
+        (0xF4 => pub bptc: ReadOnly<u32>),
+        (0xF8 => pub tsctc: ReadOnly<u32>),
+        (0xFC => pub tsctfc: ReadOnly<u32>),
+        (0x100 => pub iac: ReadOnly<u32>),
+        (0x104 => _reserved5),
+        (0x148 => pub rlpic: ReadOnly<u32>),
+        (0x14C => pub tlpic: ReadOnly<u32>),
+        (0x150 => @END),
+    },
+
+    /// The whole CSR space mapped by BAR0.
+    pub IgbRegisters {
+        (0x0000 => pub ctrl: ReadWrite<u32, CTRL::Register>),
+        (0x0004 => _reserved0),
+        (0x0008 => pub status: ReadOnly<u32, STATUS::Register>),
+        (0x000C => _reserved1),
+        (0x0010 => pub eec: ReadWrite<u32, EEC::Register>),
+        (0x0014 => pub eerd: ReadWrite<u32, EERD::Register>),
+        (0x0018 => pub ctrl_ext: ReadWrite<u32, CTRL_EXT::Register>),
+        (0x001C => pub fla: ReadWrite<u32>),
+        (0x0020 => pub mdic: ReadWrite<u32, MDIC::Register>),
+        (0x0024 => pub serdesctl: ReadWrite<u32>),
+        (0x0028 => pub fcal: ReadWrite<u32>),
+        (0x002C => pub fcah: ReadWrite<u32>),
+        (0x0030 => pub fct: ReadWrite<u32>),
+        (0x0034 => pub connsw: ReadWrite<u32>),
+        (0x0038 => pub vet: ReadWrite<u32>),
+        (0x003C => _reserved2),
+        (0x00C0 => pub icr: ReadWrite<u32, INTERRUPT::Register>),
+        (0x00C4 => pub itr: ReadWrite<u32>),
+        (0x00C8 => pub ics: WriteOnly<u32, INTERRUPT::Register>),
+        (0x00CC => _reserved3),
+        (0x00D0 => pub ims: ReadWrite<u32, INTERRUPT::Register>),
+        (0x00D4 => _reserved4),
+        (0x00D8 => pub imc: WriteOnly<u32, INTERRUPT::Register>),
+        (0x00DC => _reserved5),
+        (0x00E0 => pub iam: ReadWrite<u32, INTERRUPT::Register>),
+        (0x00E4 => _reserved6),
+        (0x0100 => pub rctl: ReadWrite<u32, RCTL::Register>),
+        (0x0104 => _reserved7),
+        (0x0170 => pub fcttv: ReadWrite<u32, FCTTV::Register>),
+        (0x0174 => _reserved8),
+        (0x0178 => pub txcw: ReadWrite<u32>),
+        (0x017C => _reserved9),
+        (0x0180 => pub rxcw: ReadOnly<u32>),
+        (0x0184 => _reserved10),
+        (0x0400 => pub tctl: ReadWrite<u32, TCTL::Register>),
+        (0x0404 => pub tctl_ext: ReadWrite<u32>),
+        (0x0408 => _reserved11),
+        (0x0410 => pub tipg: ReadWrite<u32, TIPG::Register>),
+        (0x0414 => _reserved12),
+        (0x0E00 => pub ledctl: ReadWrite<u32>),
+        (0x0E04 => pub mdicnfg: ReadWrite<u32, MDICNFG::Register>),
+        (0x0E08 => _reserved13),
+        (0x0E14 => pub phpm: ReadWrite<u32, PHPM::Register>),
+        (0x0E18 => _reserved14),
+        (0x0E30 => pub eeer: ReadWrite<u32, EEER::Register>),
+        (0x0E34 => pub eee_su: ReadWrite<u32>),
+        (0x0E38 => pub ipcnfg: ReadWrite<u32, IPCNFG::Register>),
+        (0x0E3C => _reserved15),
+        (0x1010 => pub eemngctl: ReadWrite<u32>),
+        (0x1014 => _reserved16),
+        (0x1028 => pub i2ccmd: ReadWrite<u32, I2CCMD::Register>),
+        (0x102C => pub eewr: ReadWrite<u32, EEWR::Register>),
+        (0x1030 => _reserved17),
+        (0x1514 => pub gpie: ReadWrite<u32, GPIE::Register>),
+        (0x1518 => _reserved18),
+        (0x1520 => pub eics: WriteOnly<u32>),
+        (0x1524 => pub eims: ReadWrite<u32>),
+        (0x1528 => pub eimc: WriteOnly<u32>),
+        (0x152C => pub eiac: ReadWrite<u32>),
+        (0x1530 => pub eiam: ReadWrite<u32>),
+        (0x1534 => _reserved19),
+        (0x1580 => pub eicr: ReadWrite<u32>),
+        (0x1584 => _reserved20),
+        (0x1680 => pub eitr: [ReadWrite<u32>; NUM_EITR]),
+        (0x16E4 => _reserved21),
+        (0x1700 => pub ivar: [ReadWrite<u32>; NUM_IVAR]),
+        (0x1720 => _reserved22),
+        (0x1740 => pub ivar_misc: ReadWrite<u32>),
+        (0x1744 => _reserved23),
+        (0x2160 => pub fcrtl: ReadWrite<u32, FCRTL::Register>),
+        (0x2164 => _reserved24),
+        (0x2168 => pub fcrth: ReadWrite<u32, FCRTH::Register>),
+        (0x216C => _reserved25),
+        (0x2404 => pub rxpbs: ReadWrite<u32, RXPBS::Register>),
+        (0x2408 => _reserved26),
+        (0x2460 => pub fcrtv: ReadWrite<u32>),
+        (0x2464 => _reserved27),
+        (0x3404 => pub txpbs: ReadWrite<u32, TXPBS::Register>),
+        (0x3408 => _reserved28),
+        (0x4000 => pub stats: StatsRegs),
+        (0x4150 => _reserved29),
+        (0x4200 => pub pcs_cfg0: ReadWrite<u32, PCS_CFG::Register>),
+        (0x4204 => _reserved30),
+        (0x4208 => pub pcs_lctl: ReadWrite<u32, PCS_LCTL::Register>),
+        (0x420C => pub pcs_lstat: ReadOnly<u32, PCS_LSTAT::Register>),
+        (0x4210 => _reserved31),
+        (0x4218 => pub pcs_anadv: ReadWrite<u32, PCS_AN::Register>),
+        (0x421C => pub pcs_lpab: ReadOnly<u32, PCS_AN::Register>),
+        (0x4220 => pub pcs_nptx: ReadWrite<u32>),
+        (0x4224 => pub pcs_lpabnp: ReadOnly<u32>),
+        (0x4228 => _reserved32),
+        (0x5000 => pub rxcsum: ReadWrite<u32, RXCSUM::Register>),
+        (0x5004 => pub rlpml: ReadWrite<u32>),
+        (0x5008 => pub rfctl: ReadWrite<u32>),
+        (0x500C => _reserved33),
+        (0x5200 => pub mta: [ReadWrite<u32>; NUM_MTA]),
+        (0x5400 => pub rar: [RarRegs; NUM_RAR]),
+        (0x5480 => _reserved34),
+        (0x5600 => pub vfta: [ReadWrite<u32>; NUM_VFTA]),
+        (0x5800 => _reserved35),
+        (0x5818 => pub mrqc: ReadWrite<u32>),
+        (0x581C => _reserved36),
+        (0x5820 => pub manc: ReadWrite<u32, MANC::Register>),
+        (0x5824 => _reserved37),
+        (0x5B30 => pub factps: ReadOnly<u32, FACTPS::Register>),
+        (0x5B34 => _reserved38),
+        (0x5B50 => pub swsm: ReadWrite<u32, SWSM::Register>),
+        (0x5B54 => pub fwsm: ReadOnly<u32, FWSM::Register>),
+        (0x5B58 => _reserved39),
+        (0x5B5C => pub sw_fw_sync: ReadWrite<u32, SW_FW_SYNC::Register>),
+        (0x5B60 => _reserved40),
+        (0xC000 => pub rx_queues: [RxQueueRegs; NUM_RX_QUEUES]),
+        (0xC400 => _reserved41),
+        (0xE000 => pub tx_queues: [TxQueueRegs; NUM_TX_QUEUES]),
+        (0xE400 => _reserved42),
+        (0x12018 => pub srwr: ReadWrite<u32, EEWR::Register>),
+        (0x1201C => _reserved43),
+        (0x12120 => pub invm_data: [ReadOnly<u32, INVM_DATA::Register>; NUM_INVM_DATA]),
+        (0x12220 => @END),
+    }
+}
@@ -0,0 +1,145 @@
+//! Software/firmware arbitration of the shared NVM, PHY and CSR resources through SWSM and
+//! SW_FW_SYNC, see section 4.7 of the datasheet.
+
+use core::time::Duration;
+
+use tock_registers::interfaces::{ReadWriteable, Readable, Writeable};
+
+use crate::hal::IgbHal;
+use crate::igb::Igb;
+use crate::regs::{STATUS, SWSM, SW_FW_SYNC};
+use crate::{IgbError, IgbResult};
+
+/// Deadline for acquiring the SWSM.SMBI and SWSM.SWESMBI bits.
+const HW_SEMAPHORE_TIMEOUT: Duration = Duration::from_millis(10);
+/// Interval between two attempts at taking a resource owned by someone else.
+const SWFW_SYNC_INTERVAL: Duration = Duration::from_millis(5);
+/// Number of attempts at taking a resource before giving up, one second in total.
+const SWFW_SYNC_ATTEMPTS: u32 = 200;
+
+/// Resource shared with the firmware through SW_FW_SYNC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SwFwResource {
+    /// EEPROM or flash.
+    Nvm,
+    /// PHY of this LAN port.
+    Phy,
+    /// Shared CSRs.
+    MacCsr,
+}
+
+impl SwFwResource {
+    /// Returns the name used in errors and logs.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Nvm => "nvm semaphore",
+            Self::Phy => "phy semaphore",
+            Self::MacCsr => "mac csr semaphore",
+        }
+    }
+}
+
+/// Ownership of a SW_FW_SYNC resource, released when dropped.
+pub(crate) struct SwFwGuard<'a, H: IgbHal> {
+    igb: &'a Igb<H>,
+    mask: u32,
+}
+
+impl<H: IgbHal> Drop for SwFwGuard<'_, H> {
+    fn drop(&mut self) {
+        // the bit is ours, clear it even if SWSM cannot be taken rather than leaking it
+        let locked = self.igb.get_hw_semaphore();
+        if let Err(e) = &locked {
+            warn!(
+                "releasing sw/fw sync {:#x} without SWSM: {:?}",
+                self.mask, e
+            );
+        }
+        let sync = &self.igb.regs().sw_fw_sync;
+        sync.set(sync.get() & !self.mask);
+        if locked.is_ok() {
+            self.igb.put_hw_semaphore();
+        }
+    }
+}
+
+impl<H: IgbHal> Igb<H> {
+    /// Returns the SW_FW_SYNC software bit of `resource`.
+    fn swfw_mask(&self, resource: SwFwResource) -> u32 {
+        match resource {
+            SwFwResource::Nvm => 1 << 0,
+            SwFwResource::Phy => match self.regs().status.read(STATUS::LAN_ID) {
+                0 => 1 << 1,
+                1 => 1 << 2,
+                2 => 1 << 5,
+                _ => 1 << 6,
+            },
+            SwFwResource::MacCsr => 1 << 3,
+        }
+    }
+
+    /// Returns SW_FW_SYNC, the resources owned by software in the low half and by the firmware
+    /// in the high half.
+    pub fn sw_fw_sync(&self) -> u32 {
+        self.regs().sw_fw_sync.get()
+    }
+
+    /// Takes the hardware semaphore guarding SW_FW_SYNC: SWSM.SMBI against other software
+    /// agents, then SWSM.SWESMBI against the firmware.
+    pub(crate) fn get_hw_semaphore(&self) -> IgbResult {
+        let swsm = &self.regs().swsm;
+
+        // reading SWSM sets SMBI, which is ours if it was clear before the read
+        Self::poll("swsm smbi", HW_SEMAPHORE_TIMEOUT, || {
+            (!swsm.is_set(SWSM::SMBI)).then_some(())
+        })
+        .map_err(|_| IgbError::SemaphoreTimeout("software semaphore (SWSM.SMBI)"))?;
+
+        let swesmbi = Self::poll("swsm swesmbi", HW_SEMAPHORE_TIMEOUT, || {
+            swsm.modify(SWSM::SWESMBI::SET);
+            swsm.is_set(SWSM::SWESMBI).then_some(())
+        });
+        if swesmbi.is_err() {
+            self.put_hw_semaphore();
+            return Err(IgbError::SemaphoreTimeout(
+                "firmware semaphore (SWSM.SWESMBI)",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Releases SWSM.SMBI and SWSM.SWESMBI.
+    pub(crate) fn put_hw_semaphore(&self) {
+        self.regs()
+            .swsm
+            .modify(SWSM::SMBI::CLEAR + SWSM::SWESMBI::CLEAR);
+    }
+
+    /// Takes `resource` once neither the firmware nor another software agent owns it.
+    pub(crate) fn acquire_swfw(&self, resource: SwFwResource) -> IgbResult<SwFwGuard<'_, H>> {
+        let mask = self.swfw_mask(resource);
+        let sync = &self.regs().sw_fw_sync;
+
+        for _ in 0..SWFW_SYNC_ATTEMPTS {
+            self.get_hw_semaphore()?;
+            let value = sync.extract();
+            let owned = value.read(SW_FW_SYNC::SW) | value.read(SW_FW_SYNC::FW);
+            if owned & mask == 0 {
+                sync.set(value.get() | mask);
+                self.put_hw_semaphore();
+                return Ok(SwFwGuard { igb: self, mask });
+            }
+            self.put_hw_semaphore();
+            let _ = H::wait_until(SWFW_SYNC_INTERVAL);
+        }
+
+        error!(
+            "{} still owned after {:?}: sw_fw_sync {:#x}",
+            resource.name(),
+            SWFW_SYNC_INTERVAL * SWFW_SYNC_ATTEMPTS,
+            sync.get()
+        );
+        Err(IgbError::SemaphoreTimeout(resource.name()))
+    }
+}
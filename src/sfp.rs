@@ -0,0 +1,269 @@
+//! SFP modules of the fiber and SerDes ports, identified from their SFF-8472 EEPROM.
+
+use core::str;
+
+use tock_registers::interfaces::ReadWriteable;
+
+use crate::hal::IgbHal;
+use crate::i2c::SFP_DIAG_PAGE;
+use crate::igb::Igb;
+use crate::link::LinkModes;
+use crate::phy::{Duplex, LinkSpeed};
+use crate::regs::CTRL_EXT;
+use crate::{IgbError, IgbResult};
+
+/// Bytes of page A0h decoded by [`SfpModule`], up to the diagnostic monitoring type.
+pub const SFP_ID_LEN: usize = 96;
+/// Identifier, the form factor of the module.
+const SFF_IDENTIFIER: usize = 0;
+/// Ethernet compliance codes.
+const SFF_ETH_COMPLIANCE: usize = 6;
+/// Nominal signalling rate, in units of 100 MBd.
+const SFF_BIT_RATE: usize = 12;
+/// Vendor name, ASCII padded with spaces.
+const SFF_VENDOR_NAME: usize = 20;
+/// Vendor part number.
+const SFF_VENDOR_PN: usize = 40;
+/// Vendor serial number.
+const SFF_VENDOR_SN: usize = 68;
+/// Length of the vendor name, part number and serial number.
+const SFF_STRING_LEN: usize = 16;
+/// Diagnostic monitoring type.
+const SFF_DIAG_TYPE: usize = 92;
+/// Digital diagnostic monitoring is implemented.
+const SFF_DIAG_DDM: u8 = 1 << 6;
+/// Diagnostics are internally calibrated.
+const SFF_DIAG_INTERNAL_CAL: u8 = 1 << 5;
+
+/// SFP or SFP+ module.
+const SFF_IDENTIFIER_SFP: u8 = 0x03;
+/// Module soldered to the board.
+const SFF_IDENTIFIER_SOLDERED: u8 = 0x02;
+/// 1000BASE-SX.
+const SFF_ETH_1000BASE_SX: u8 = 1 << 0;
+/// 1000BASE-LX.
+const SFF_ETH_1000BASE_LX: u8 = 1 << 1;
+/// 1000BASE-CX.
+const SFF_ETH_1000BASE_CX: u8 = 1 << 2;
+/// 1000BASE-T.
+const SFF_ETH_1000BASE_T: u8 = 1 << 3;
+/// 100BASE-LX10.
+const SFF_ETH_100BASE_LX: u8 = 1 << 4;
+/// 100BASE-FX.
+const SFF_ETH_100BASE_FX: u8 = 1 << 5;
+
+/// Offset in page A2h of the real time diagnostics.
+const SFF_DDM_VALUES: u16 = 96;
+/// Length of temperature, supply voltage, TX bias, TX power and RX power.
+pub const SFP_DDM_LEN: usize = 10;
+
+/// Medium behind an SFP module, deciding how the MAC talks to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SfpMedia {
+    /// 1000BASE-SX, LX or CX, run as 1000BASE-X by the PCS.
+    Fiber1000BaseX,
+    /// 1000BASE-T module with a copper PHY, run as SGMII.
+    Copper1000BaseT,
+    /// 100BASE-FX or LX10, run as SGMII at 100 Mb/s.
+    Fiber100BaseFx,
+    /// No Ethernet compliance code the MAC can run.
+    Unknown,
+}
+
+/// Identification of an SFP module, from page A0h of its EEPROM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SfpModule {
+    /// SFF-8024 identifier, 0x03 for SFP modules.
+    pub identifier: u8,
+    /// Ethernet compliance codes.
+    pub eth_compliance: u8,
+    /// Nominal signalling rate in MBd.
+    pub bit_rate_mbd: u32,
+    /// Digital diagnostic monitoring is available in page A2h.
+    pub ddm: bool,
+    ddm_internal: bool,
+    vendor: [u8; SFF_STRING_LEN],
+    part_number: [u8; SFF_STRING_LEN],
+    serial_number: [u8; SFF_STRING_LEN],
+}
+
+impl SfpModule {
+    /// Decodes the first bytes of page A0h.
+    pub fn parse(id: &[u8; SFP_ID_LEN]) -> Self {
+        let string = |offset: usize| {
+            let mut s = [0; SFF_STRING_LEN];
+            s.copy_from_slice(&id[offset..offset + SFF_STRING_LEN]);
+            s
+        };
+        Self {
+            identifier: id[SFF_IDENTIFIER],
+            eth_compliance: id[SFF_ETH_COMPLIANCE],
+            bit_rate_mbd: id[SFF_BIT_RATE] as u32 * 100,
+            ddm: id[SFF_DIAG_TYPE] & SFF_DIAG_DDM != 0,
+            ddm_internal: id[SFF_DIAG_TYPE] & SFF_DIAG_INTERNAL_CAL != 0,
+            vendor: string(SFF_VENDOR_NAME),
+            part_number: string(SFF_VENDOR_PN),
+            serial_number: string(SFF_VENDOR_SN),
+        }
+    }
+
+    /// Returns an ASCII field without its padding, or an empty string if it is not ASCII.
+    fn ascii(field: &[u8; SFF_STRING_LEN]) -> &str {
+        str::from_utf8(field)
+            .map(|s| s.trim_end_matches([' ', '\0']))
+            .unwrap_or("")
+    }
+
+    /// Returns the vendor name.
+    pub fn vendor(&self) -> &str {
+        Self::ascii(&self.vendor)
+    }
+
+    /// Returns the vendor part number.
+    pub fn part_number(&self) -> &str {
+        Self::ascii(&self.part_number)
+    }
+
+    /// Returns the vendor serial number.
+    pub fn serial_number(&self) -> &str {
+        Self::ascii(&self.serial_number)
+    }
+
+    /// Returns the medium of the module, from its Ethernet compliance codes.
+    pub fn media(&self) -> SfpMedia {
+        let eth = self.eth_compliance;
+        if eth & (SFF_ETH_1000BASE_SX | SFF_ETH_1000BASE_LX | SFF_ETH_1000BASE_CX) != 0 {
+            SfpMedia::Fiber1000BaseX
+        } else if eth & SFF_ETH_1000BASE_T != 0 {
+            SfpMedia::Copper1000BaseT
+        } else if eth & (SFF_ETH_100BASE_LX | SFF_ETH_100BASE_FX) != 0 {
+            SfpMedia::Fiber100BaseFx
+        } else {
+            SfpMedia::Unknown
+        }
+    }
+
+    /// Returns the link modes the module supports.
+    pub fn supported_modes(&self) -> LinkModes {
+        match self.media() {
+            SfpMedia::Fiber1000BaseX => LinkModes::SPEED_1000_FULL,
+            SfpMedia::Copper1000BaseT => LinkModes::COPPER,
+            SfpMedia::Fiber100BaseFx => LinkModes::SPEED_100_FULL,
+            SfpMedia::Unknown => LinkModes::empty(),
+        }
+    }
+}
+
+/// Real time diagnostics of an SFP module, from page A2h of its EEPROM, in the fixed point
+/// units of SFF-8472.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SfpDiagnostics {
+    /// Module temperature in 1/256 degree Celsius.
+    pub temperature: i16,
+    /// Supply voltage in units of 100 uV.
+    pub vcc: u16,
+    /// Laser bias current in units of 2 uA.
+    pub tx_bias: u16,
+    /// Transmitted optical power in units of 0.1 uW.
+    pub tx_power: u16,
+    /// Received optical power in units of 0.1 uW.
+    pub rx_power: u16,
+}
+
+impl SfpDiagnostics {
+    /// Decodes the internally calibrated values at offset 96 of page A2h.
+    pub fn parse(ddm: &[u8; SFP_DDM_LEN]) -> Self {
+        let word = |index: usize| u16::from_be_bytes([ddm[2 * index], ddm[2 * index + 1]]);
+        Self {
+            temperature: word(0) as i16,
+            vcc: word(1),
+            tx_bias: word(2),
+            tx_power: word(3),
+            rx_power: word(4),
+        }
+    }
+}
+
+impl<H: IgbHal> Igb<H> {
+    /// Reads the identification of the SFP module plugged in the port.
+    pub fn sfp_module(&self) -> IgbResult<SfpModule> {
+        let mut id = [0; SFP_ID_LEN];
+        self.read_sfp(0, &mut id)?;
+        let module = SfpModule::parse(&id);
+        if !matches!(
+            module.identifier,
+            SFF_IDENTIFIER_SFP | SFF_IDENTIFIER_SOLDERED
+        ) {
+            return Err(IgbError::HardwareState("no sfp module found"));
+        }
+        Ok(module)
+    }
+
+    /// Reads the real time diagnostics of the SFP module plugged in the port.
+    pub fn sfp_diagnostics(&self) -> IgbResult<SfpDiagnostics> {
+        let module = self.sfp_module()?;
+        if !module.ddm {
+            return Err(IgbError::HardwareState("sfp module has no diagnostics"));
+        }
+        if !module.ddm_internal {
+            return Err(IgbError::HardwareState(
+                "sfp module diagnostics are externally calibrated",
+            ));
+        }
+        let mut ddm = [0; SFP_DDM_LEN];
+        self.read_sfp(SFP_DIAG_PAGE + SFF_DDM_VALUES, &mut ddm)?;
+        Ok(SfpDiagnostics::parse(&ddm))
+    }
+
+    /// Switches the port to the link mode of the media of `module`, looking for the PHY behind
+    /// SGMII again for copper and 100BASE-FX modules.
+    fn sfp_select_mode(&mut self, module: &SfpModule) -> IgbResult<SfpMedia> {
+        info!(
+            "igb: sfp {} {}, {:?}",
+            module.vendor(),
+            module.part_number(),
+            module.media()
+        );
+        match module.media() {
+            SfpMedia::Fiber1000BaseX => {
+                self.regs().ctrl_ext.modify(CTRL_EXT::LINK_MODE::SerDes);
+            }
+            SfpMedia::Copper1000BaseT | SfpMedia::Fiber100BaseFx => {
+                self.regs().ctrl_ext.modify(CTRL_EXT::LINK_MODE::Sgmii);
+                self.detect_phy();
+            }
+            SfpMedia::Unknown => {
+                return Err(IgbError::HardwareState("sfp module media not supported"));
+            }
+        }
+        Ok(module.media())
+    }
+
+    /// Starts the link of an SFP port as 1000BASE-X or SGMII, depending on the module, without
+    /// waiting for it. Optics without a readable EEPROM run 1000BASE-X.
+    pub(crate) fn sfp_start_link(&mut self) -> IgbResult {
+        let media = match self.sfp_module() {
+            Ok(module) => self.sfp_select_mode(&module)?,
+            Err(e) => {
+                debug!("no sfp module identified, assuming 1000base-x: {:?}", e);
+                SfpMedia::Fiber1000BaseX
+            }
+        };
+        match media {
+            SfpMedia::Fiber1000BaseX => {
+                self.serdes_start_link(true);
+                Ok(())
+            }
+            _ => self.sgmii_start_link(),
+        }
+    }
+
+    /// Sets up the link of an SFP port as 1000BASE-X or SGMII, depending on the module.
+    pub fn sfp_link_setup(&mut self) -> IgbResult<(LinkSpeed, Duplex)> {
+        let module = self.sfp_module()?;
+        match self.sfp_select_mode(&module)? {
+            SfpMedia::Fiber1000BaseX => self.serdes_link_setup(true),
+            _ => self.sgmii_link_setup(),
+        }
+    }
+}
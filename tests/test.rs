@@ -6,8 +6,29 @@
 
 extern crate alloc;
 
-use bare_test::{driver::device_tree::get_device_tree, fdt::PciSpace, mem::mmu::iomap, println};
-use igb_driver::Igb;
+use alloc::sync::Arc;
+use core::{
+    alloc::Layout,
+    ptr::NonNull,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use bare_test::{
+    driver::device_tree::get_device_tree,
+    fdt::PciSpace,
+    mem::{
+        dma::{alloc_coherent, dealloc_coherent, BusAddr, DMAMem},
+        mmu::{iomap, va_offset},
+    },
+    println,
+    time::since_boot,
+};
+use igb_driver::{
+    CableDiagnostics, Duplex, IgbDevice, IgbError, IgbHal, IgbMacType, IgbNetBuf, IgbPhyType,
+    LinkModes, LinkSpeed, Loopback, MdiMode, MemPool, NicDevice, NicResolution, PairStatus,
+    PhysAddr, SfpDiagnostics, SfpMedia, SfpModule, INTEL_VEND, SFP_DDM_LEN, SFP_ID_LEN,
+};
 use log::{debug, info};
 use pcie::*;
 
@@ -19,14 +40,584 @@ fn it_works1() {
     assert_eq!(1, 1);
 }
 
+const QS: usize = 512;
+const POOL_ENTRIES: usize = 4096;
+const POOL_ENTRY_SIZE: usize = 2048;
+
+struct TestHal;
+
+unsafe impl IgbHal for TestHal {
+    fn dma_alloc(size: usize) -> (PhysAddr, NonNull<u8>) {
+        let layout = Layout::from_size_align(size, 0x1000).unwrap();
+        let dma = unsafe { alloc_coherent(layout) }.expect("no dma memory");
+        (dma.bus_addr.as_u64() as PhysAddr, dma.cpu_addr)
+    }
+
+    unsafe fn dma_dealloc(paddr: PhysAddr, vaddr: NonNull<u8>, size: usize) -> i32 {
+        let layout = Layout::from_size_align(size, 0x1000).unwrap();
+        let dma = DMAMem {
+            cpu_addr: vaddr,
+            bus_addr: BusAddr::new(paddr as u64),
+        };
+        unsafe { dealloc_coherent(dma, layout) };
+        0
+    }
+
+    unsafe fn mmio_phys_to_virt(paddr: PhysAddr, size: usize) -> NonNull<u8> {
+        iomap(paddr.into(), size)
+    }
+
+    unsafe fn mmio_virt_to_phys(vaddr: NonNull<u8>, _size: usize) -> PhysAddr {
+        vaddr.as_ptr() as usize - va_offset()
+    }
+
+    fn wait_until(duration: Duration) -> Result<(), &'static str> {
+        let deadline = since_boot() + duration;
+        while since_boot() < deadline {
+            core::hint::spin_loop();
+        }
+        Ok(())
+    }
+}
+
 #[test_case]
 fn test_igb() {
     let igb = get_igb();
 
-    debug!("igb start");
+    debug!(
+        "igb start, mac type: {:?}, nvm type: {:?}, rx queues: {}",
+        igb.igb().mac_type(),
+        igb.igb().nvm_type(),
+        igb.igb().capabilities().rx_queues
+    );
+}
+
+#[test_case]
+fn test_igb_mdio() {
+    let igb = get_igb();
+    let igb = igb.igb();
+
+    let id = igb.read_phy_id().unwrap();
+    assert_eq!(id, igb.phy_id());
+    assert!(id != 0 && id != u32::MAX);
+    debug!("phy {:#010x} at address {}", id, igb.phy_addr());
+
+    let phy = igb.phy();
+    assert_eq!(phy.phy_type(), igb.phy_type());
+    debug!(
+        "phy type {:?}, resolved {:?}",
+        igb.phy_type(),
+        phy.speed_duplex(igb).unwrap()
+    );
+}
+
+#[test_case]
+fn test_igb_autoneg() {
+    let igb = get_igb();
+
+    let (speed, duplex) = igb.igb().phy_link_setup().unwrap();
+    debug!("negotiated {} Mb/s {:?} duplex", speed.mbps(), duplex);
+    assert_eq!(
+        igb.igb().phy().speed_duplex(igb.igb()).unwrap(),
+        Some((speed, duplex))
+    );
+}
+
+#[test_case]
+fn test_igb_link_state() {
+    let mut igb = get_igb();
+    let changes = Arc::new(AtomicUsize::new(0));
+    let counter = changes.clone();
+    igb.set_link_callback(move |state| {
+        debug!("link callback: {:?}", state);
+        counter.fetch_add(1, Ordering::Relaxed);
+    });
+
+    let (speed, duplex) = igb.igb().phy_link_setup().unwrap();
+    let state = igb.check_link().unwrap();
+    assert!(state.up);
+    assert_eq!((state.speed, state.duplex), (speed, duplex));
+    assert_eq!(changes.load(Ordering::Relaxed), 1);
+
+    // no transition, no callback
+    assert_eq!(igb.check_link().unwrap(), state);
+    assert_eq!(changes.load(Ordering::Relaxed), 1);
+}
+
+#[test_case]
+fn test_igb_force_link() {
+    let igb = get_igb();
+    let hw = igb.igb();
+
+    hw.force_link(LinkSpeed::Speed100, Duplex::Full).unwrap();
+    assert_eq!(
+        hw.phy().speed_duplex(hw).unwrap(),
+        Some((LinkSpeed::Speed100, Duplex::Full))
+    );
+
+    hw.restore_autoneg().unwrap();
+    let (speed, duplex) = hw.phy_link_setup().unwrap();
+    debug!(
+        "back to autonegotiation: {} Mb/s {:?} duplex",
+        speed.mbps(),
+        duplex
+    );
+}
+
+#[test_case]
+fn test_igb_link_modes() {
+    let mut igb = get_igb();
+
+    let supported = igb.igb().supported_modes();
+    debug!("supported {:?}", supported);
+    assert!(supported.contains(LinkModes::SPEED_100_FULL));
+
+    let capped = LinkModes::SPEED_100_FULL | LinkModes::SPEED_10_FULL;
+    igb.set_advertised_modes(capped).unwrap();
+    assert_eq!(igb.igb().advertised_modes(), capped);
+    let (speed, duplex) = igb.igb().phy_link_setup().unwrap();
+    assert_eq!((speed, duplex), (LinkSpeed::Speed100, Duplex::Full));
+    debug!("partner {:?}", igb.igb().partner_modes().unwrap());
+
+    igb.set_advertised_modes(supported).unwrap();
+}
+
+#[test_case]
+fn test_igb_cable_diagnostics() {
+    let igb = get_igb();
+
+    let hw = igb.igb();
+
+    match hw.phy_type() {
+        // the IGP PHYs have no cable tester
+        IgbPhyType::Igp => assert!(matches!(
+            hw.cable_diagnostics(),
+            Err(IgbError::UnsupportedDevice(_))
+        )),
+        _ => match hw.cable_diagnostics() {
+            Ok(diagnostics) => debug!("cable diagnostics {:?}", diagnostics),
+            // the 82580 only measures the length of 1000 Mb/s links
+            Err(e) => assert!(matches!(e, IgbError::HardwareState(_))),
+        },
+    }
+}
+
+#[test_case]
+fn test_cable_decode() {
+    for (nibble, status) in [
+        (0b0000, PairStatus::Unknown),
+        (0b0001, PairStatus::Ok),
+        (0b0010, PairStatus::Open),
+        (0b0011, PairStatus::Short),
+        (0b1000, PairStatus::Short),
+        (0b1001, PairStatus::Unknown),
+        (0b1111, PairStatus::Unknown),
+    ] {
+        assert_eq!(PairStatus::from_vct(nibble), status);
+    }
+    // only the low nibble is decoded
+    assert_eq!(PairStatus::from_vct(0x12), PairStatus::Open);
+
+    for (index, length) in [
+        (0, Some(25)),
+        (1, Some(65)),
+        (2, Some(95)),
+        (3, Some(125)),
+        (4, Some(140)),
+        (5, None),
+        (7, None),
+    ] {
+        let diagnostics = CableDiagnostics::from_m88_pssr(index << 7);
+        assert_eq!(diagnostics.length_m(), length);
+        assert!(diagnostics
+            .pairs
+            .iter()
+            .all(|pair| pair.status == PairStatus::Unknown));
+    }
+}
+
+#[test_case]
+fn test_igb_mdi_downshift() {
+    let igb = get_igb();
+    let hw = igb.igb();
+
+    // every PHY type either implements the setting or reports it unsupported
+    let supported =
+        |r: Result<(), IgbError>| matches!(r, Ok(()) | Err(IgbError::UnsupportedDevice(_)));
+    assert!(supported(hw.set_mdi_mode(MdiMode::Auto)));
+    assert!(supported(hw.set_downshift(Some(3))));
+    assert!(supported(hw.set_downshift(None)));
+
+    hw.phy_link_setup().unwrap();
+    let state = hw.link_state();
+    assert!(state.up);
+    // all the supported PHY types report the resolved crossover
+    assert!(state.mdi.is_some());
+}
+
+#[test_case]
+fn test_igb_eee() {
+    let igb = get_igb();
+    let hw = igb.igb();
+
+    if !hw.capabilities().eee {
+        assert!(matches!(
+            hw.set_eee(true),
+            Err(IgbError::UnsupportedDevice(_))
+        ));
+        return;
+    }
+    hw.set_eee(true).unwrap();
+    hw.phy_link_setup().unwrap();
+    debug!("eee status {:?}", hw.eee_status().unwrap());
+    debug!("lpi counters {:?}", hw.lpi_counters().unwrap());
+    hw.set_eee(false).unwrap();
+    assert!(!hw.eee_status().unwrap().enabled);
+}
+
+#[test_case]
+fn test_igb_sfp() {
+    let igb = get_igb();
+    let hw = igb.igb();
+
+    // only fiber and serdes ports have an sfp cage
+    let module = match hw.sfp_module() {
+        Ok(module) => module,
+        Err(e) => {
+            debug!("no sfp module: {:?}", e);
+            return;
+        }
+    };
+    debug!(
+        "sfp {} {} {}, {:?}, {} MBd",
+        module.vendor(),
+        module.part_number(),
+        module.serial_number(),
+        module.media(),
+        module.bit_rate_mbd
+    );
+    if module.ddm {
+        debug!("sfp diagnostics {:?}", hw.sfp_diagnostics());
+    }
+}
+
+#[test_case]
+fn test_sfp_parse() {
+    let mut id = [0; SFP_ID_LEN];
+    id[0] = 0x03;
+    id[6] = 0x01;
+    id[12] = 13;
+    id[20..36].copy_from_slice(b"FINISAR CORP.   ");
+    id[40..56].copy_from_slice(b"FTLF8519P2BNL   ");
+    id[68..84].copy_from_slice(b"PXA1234\0\0\0\0\0\0\0\0\0");
+    id[92] = 0x68;
+
+    let module = SfpModule::parse(&id);
+    assert_eq!(module.identifier, 0x03);
+    assert_eq!(module.vendor(), "FINISAR CORP.");
+    assert_eq!(module.part_number(), "FTLF8519P2BNL");
+    assert_eq!(module.serial_number(), "PXA1234");
+    assert_eq!(module.bit_rate_mbd, 1300);
+    assert!(module.ddm);
+    assert_eq!(module.media(), SfpMedia::Fiber1000BaseX);
+    assert_eq!(module.supported_modes(), LinkModes::SPEED_1000_FULL);
+
+    for (compliance, media) in [
+        (0x08, SfpMedia::Copper1000BaseT),
+        (0x20, SfpMedia::Fiber100BaseFx),
+        (0x10, SfpMedia::Fiber100BaseFx),
+        (0x00, SfpMedia::Unknown),
+    ] {
+        id[6] = compliance;
+        assert_eq!(SfpModule::parse(&id).media(), media);
+    }
+    id[6] = 0;
+    assert!(SfpModule::parse(&id).supported_modes().is_empty());
+
+    let ddm: [u8; SFP_DDM_LEN] = [0x19, 0x80, 0x80, 0x84, 0x17, 0x70, 0x1F, 0x40, 0x0F, 0xA0];
+    assert_eq!(
+        SfpDiagnostics::parse(&ddm),
+        SfpDiagnostics {
+            temperature: 0x1980,
+            vcc: 32900,
+            tx_bias: 6000,
+            tx_power: 8000,
+            rx_power: 4000,
+        }
+    );
+    // the temperature is two's complement
+    let cold = [0xFF, 0x00, 0, 0, 0, 0, 0, 0, 0, 0];
+    assert_eq!(SfpDiagnostics::parse(&cold).temperature, -256);
+}
+
+#[test_case]
+fn test_igb_i2c() {
+    let igb = get_igb();
+    let hw = igb.igb();
+
+    // each I2CCMD transaction completes with READY, with E set when no module answers
+    let mut first = [0; 1];
+    let first_result = hw.read_sfp(0, &mut first);
+    assert!(matches!(
+        first_result,
+        Ok(()) | Err(IgbError::HardwareState(_))
+    ));
+
+    // the transaction releases the PHY semaphore and reads the same byte again
+    let mut second = [0; 1];
+    let second_result = hw.read_sfp(0, &mut second);
+    assert_eq!(first_result.is_ok(), second_result.is_ok());
+    if first_result.is_ok() {
+        assert_eq!(first, second);
+    }
+
+    // page A2h is addressed through the lowest bit of the device address
+    let diag_result = hw.read_sfp(0x100, &mut [0; 1]);
+    assert!(matches!(
+        diag_result,
+        Ok(()) | Err(IgbError::HardwareState(_))
+    ));
+    debug!("sfp identifier {:#04x}, {:?}", first[0], first_result);
+}
+
+#[test_case]
+fn test_igb_flow_control() {
+    let mut igb = get_igb();
+
+    igb.set_flow_control(NicResolution::IgbFcNone).unwrap();
+    igb.igb().phy_link_setup().unwrap();
+    assert_eq!(igb.igb().flow_control(), NicResolution::IgbFcNone);
+
+    igb.set_flow_control(NicResolution::IgbFcFull).unwrap();
+    let (_, duplex) = igb.igb().phy_link_setup().unwrap();
+    // the emulated link partner advertises symmetric pause, half duplex links run without
+    let expected = match duplex {
+        Duplex::Full => NicResolution::IgbFcFull,
+        Duplex::Half => NicResolution::IgbFcNone,
+    };
+    assert_eq!(igb.igb().requested_flow_control(), NicResolution::IgbFcFull);
+    assert_eq!(igb.igb().flow_control(), expected);
+}
+
+#[test_case]
+fn test_flow_control_resolution() {
+    use NicResolution::*;
+
+    // (local PAUSE, local ASM_DIR, partner PAUSE, partner ASM_DIR) with full flow control
+    // requested, following table 28B-3 of IEEE 802.3
+    let table = [
+        ((false, false), (false, false), IgbFcNone),
+        ((false, false), (false, true), IgbFcNone),
+        ((false, false), (true, false), IgbFcNone),
+        ((false, false), (true, true), IgbFcNone),
+        ((false, true), (false, false), IgbFcNone),
+        ((false, true), (false, true), IgbFcNone),
+        ((false, true), (true, false), IgbFcNone),
+        ((false, true), (true, true), IgbFcTxPause),
+        ((true, false), (false, false), IgbFcNone),
+        ((true, false), (false, true), IgbFcNone),
+        ((true, false), (true, false), IgbFcFull),
+        ((true, false), (true, true), IgbFcFull),
+        ((true, true), (false, false), IgbFcNone),
+        ((true, true), (false, true), IgbFcRxPause),
+        ((true, true), (true, false), IgbFcFull),
+        ((true, true), (true, true), IgbFcFull),
+    ];
+    for (local, partner, resolved) in table {
+        assert_eq!(IgbFcFull.resolve(local, partner), resolved);
+        // asking for reception only narrows symmetric pause down to it
+        let rx_only = match resolved {
+            IgbFcFull => IgbFcRxPause,
+            other => other,
+        };
+        assert_eq!(IgbFcRxPause.resolve(local, partner), rx_only);
+    }
+
+    assert_eq!(IgbFcNone.advertisement(), (false, false));
+    assert_eq!(IgbFcTxPause.advertisement(), (false, true));
+    assert_eq!(IgbFcRxPause.advertisement(), (true, true));
+    assert_eq!(IgbFcFull.advertisement(), (true, true));
+}
+
+#[test_case]
+fn test_igb_nvm() {
+    let igb = get_igb();
+    let igb = igb.igb();
+
+    igb.validate_nvm_checksum().unwrap();
+    assert_eq!(igb.read_nvm_mac_addr().unwrap(), igb.perm_mac_addr());
+
+    let mut words = [0u16; 3];
+    igb.read_nvm_words(0, &mut words).unwrap();
+    assert_eq!(words[0], igb.read_nvm(0).unwrap());
+    debug!(
+        "nvm init control words: {:04x?}",
+        igb.read_init_control_words().unwrap()
+    );
+
+    assert!(igb.read_nvm(0x4000).is_err());
+}
+
+#[test_case]
+fn test_igb_semaphore() {
+    let igb = get_igb();
+    let hw = igb.igb();
+
+    // no resource is held by software between two calls
+    let before = hw.sw_fw_sync();
+    assert_eq!(before & 0xFFFF, 0);
+
+    // each accessor releases the PHY semaphore, or the next one would time out
+    let id = hw.read_mdi(2).unwrap();
+    assert_eq!(hw.sw_fw_sync(), before);
+    assert_eq!(hw.read_mdi(2).unwrap(), id);
+    assert_eq!(hw.read_mdi_paged(0, 2).unwrap(), id);
+    assert_eq!(hw.sw_fw_sync(), before);
+
+    // and the NVM semaphore likewise
+    let word = hw.read_nvm(0).unwrap();
+    assert_eq!(hw.read_nvm(0).unwrap(), word);
+    assert_eq!(hw.sw_fw_sync(), before);
+}
+
+#[test_case]
+fn test_igb_mac_addr() {
+    let mut igb = get_igb();
+
+    let perm = igb.igb().perm_mac_addr();
+    assert_eq!(igb.get_mac_addr(), perm);
+
+    let local = [0x02, 0x00, 0x00, 0x12, 0x34, 0x56];
+    igb.set_mac_addr(local).unwrap();
+    assert_eq!(igb.get_mac_addr(), local);
+
+    assert!(igb.set_mac_addr([0x01, 0, 0, 0, 0, 1]).is_err());
+    assert!(igb.set_mac_addr([0; 6]).is_err());
+
+    igb.set_mac_addr(perm).unwrap();
+}
+
+#[test_case]
+fn test_igb_send() {
+    let mut igb = get_igb();
+    let pool = MemPool::allocate::<TestHal>(64, POOL_ENTRY_SIZE).unwrap();
+
+    let mac = igb.get_mac_addr();
+    println!("mac: {:02x?}, link speed: {}", mac, igb.get_link_speed());
+
+    // broadcast frame with an experimental ethertype
+    let mut buf = IgbNetBuf::alloc(&pool, 64).unwrap();
+    let packet = buf.packet_mut();
+    packet[0..6].fill(0xff);
+    packet[6..12].copy_from_slice(&mac);
+    packet[12..14].copy_from_slice(&[0x88, 0xb5]);
+
+    assert!(igb.can_send(0).unwrap());
+    igb.send(0, buf).unwrap();
+    igb.recycle_tx_buffers(0).unwrap();
+    assert!(igb.send(1, IgbNetBuf::alloc(&pool, 64).unwrap()).is_err());
+}
+
+#[test_case]
+fn test_igb_mac_loopback() {
+    let mut igb = get_igb();
+    let pool = MemPool::allocate::<TestHal>(64, POOL_ENTRY_SIZE).unwrap();
+    let mac = igb.get_mac_addr();
+
+    igb.igb().set_loopback(Loopback::Mac).unwrap();
+    assert!(igb.igb().link_state().up);
+
+    let mut buf = IgbNetBuf::alloc(&pool, 64).unwrap();
+    let packet = buf.packet_mut();
+    packet[0..6].fill(0xff);
+    packet[6..12].copy_from_slice(&mac);
+    packet[12..14].copy_from_slice(&[0x88, 0xb5]);
+    igb.send(0, buf).unwrap();
+
+    let deadline = since_boot() + Duration::from_secs(1);
+    let mut received = 0;
+    while received == 0 && since_boot() < deadline {
+        if let Ok(n) = igb.receive_packets(0, 1, |rx| {
+            assert_eq!(&rx.packet()[6..12], &mac);
+        }) {
+            received = n;
+        }
+    }
+    assert_eq!(received, 1);
+    igb.recycle_tx_buffers(0).unwrap();
+
+    igb.igb().set_loopback(Loopback::None).unwrap();
+}
+
+#[test_case]
+fn test_igb_power() {
+    let mut igb = get_igb();
+
+    igb.close().unwrap();
+    let deadline = since_boot() + Duration::from_secs(1);
+    while igb.igb().link_state().up && since_boot() < deadline {}
+    assert!(!igb.igb().link_state().up);
+    igb.open().unwrap();
+    let (speed, duplex) = igb.igb().phy_link_setup().unwrap();
+    assert!(igb.igb().link_state().up);
+    debug!(
+        "link back after open: {} Mb/s {:?} duplex",
+        speed.mbps(),
+        duplex
+    );
+
+    let hw = igb.igb();
+    match hw.mac_type() {
+        IgbMacType::I350 | IgbMacType::I210 | IgbMacType::I211 => {
+            hw.set_smart_power_down(true).unwrap();
+            hw.set_link_down_power_saving(true).unwrap();
+            hw.set_smart_power_down(false).unwrap();
+            hw.set_link_down_power_saving(false).unwrap();
+        }
+        _ => assert!(matches!(
+            hw.set_smart_power_down(true),
+            Err(IgbError::UnsupportedDevice(_))
+        )),
+    }
+}
+
+#[test_case]
+fn test_igb_invalid_arguments() {
+    let mut igb = get_igb();
+    let invalid = |r: Result<_, IgbError>| matches!(r, Err(IgbError::InvalidArgument(_)));
+
+    assert!(invalid(igb.set_advertised_modes(LinkModes::empty())));
+
+    let hw = igb.igb();
+
+    assert!(invalid(hw.set_rx_packet_buffer_size(0x80)));
+    assert!(invalid(hw.set_tx_packet_buffer_size(0x40)));
+    assert!(invalid(hw.forcing_mac_speed(0b11)));
+    assert!(invalid(hw.forcing_mac_speed(u32::MAX)));
+    assert!(invalid(hw.forcing_duplex_mode(2)));
+    assert!(invalid(hw.force_link(LinkSpeed::Speed1000, Duplex::Full)));
+    assert!(invalid(hw.set_downshift(Some(0))));
+    assert!(invalid(hw.set_downshift(Some(9))));
+    assert!(invalid(hw.read_sfp(0x1FF, &mut [0; 2])));
+    assert!(invalid(hw.write_mdi(32, 0)));
+    assert!(invalid(hw.read_mdi(32).map(|_| ())));
+    assert!(invalid(hw.read_mdi_paged(0, 32).map(|_| ())));
+
+    let pool = MemPool::allocate::<TestHal>(16, POOL_ENTRY_SIZE).unwrap();
+    assert!(invalid(IgbNetBuf::construct(16, &pool, 64).map(|_| ())));
+    // an entry already held, then one allocated, is not wrapped a second time
+    let held = IgbNetBuf::construct(0, &pool, 64).unwrap();
+    assert!(invalid(IgbNetBuf::construct(0, &pool, 64).map(|_| ())));
+    drop(held);
+    let allocated = IgbNetBuf::alloc(&pool, 64).unwrap();
+    assert!(invalid(
+        IgbNetBuf::construct(allocated.pool_entry(), &pool, 64).map(|_| ())
+    ));
+    drop(allocated);
+    assert!(IgbNetBuf::alloc(&pool, POOL_ENTRY_SIZE).is_err());
 }
 
-fn get_igb() -> Igb {
+fn get_igb() -> IgbDevice<TestHal, QS> {
     let fdt = get_device_tree().unwrap();
     let pcie = fdt
         .find_compatible(&["pci-host-ecam-generic"])
@@ -72,7 +663,8 @@ fn get_igb() -> Igb {
                     | CommandRegister::BUS_MASTER_ENABLE
             });
 
-            if ep.vendor_id == 0x8086 && ep.device_id == 0x10C9 {
+            let device_id = ep.device_id;
+            if ep.vendor_id == INTEL_VEND && IgbMacType::from_device_id(device_id).is_some() {
                 let bar_addr;
                 let bar_size;
                 match ep.bar {
@@ -93,7 +685,8 @@ fn get_igb() -> Igb {
 
                 let addr = iomap(bar_addr.into(), bar_size);
 
-                let igb = Igb::new(addr);
+                let pool = MemPool::allocate::<TestHal>(POOL_ENTRIES, POOL_ENTRY_SIZE).unwrap();
+                let igb = IgbDevice::<TestHal, QS>::new(addr, device_id, 1, 1, &pool).unwrap();
                 return igb;
             }
         }